@@ -0,0 +1,523 @@
+// The unsigned half of the pair. It shares the magnitude helpers with
+// `BigInt`, and converting between the two just moves the limbs over.
+
+use crate::locale::NumberLocale;
+use crate::words::{Spelled, Spelling};
+use crate::{
+    add_assign_limbs, cmp_limbs, div_rem_limbs, mul_limbs, radix, sub_assign_limbs,
+    trim_zero_limbs, BigInt, DivisionByZero, ParseError, ParseErrorKind, TryFromBigIntError,
+};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub};
+use std::str::FromStr;
+
+// Base 2^32 limbs, least significant first, with no trailing zero limbs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BigUint {
+    limbs: Vec<u32>,
+}
+
+impl BigUint {
+    pub fn new() -> Self {
+        BigUint { limbs: Vec::new() }
+    }
+
+    pub fn zero() -> Self {
+        BigUint::new()
+    }
+
+    pub fn one() -> Self {
+        BigUint { limbs: vec![1] }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    // `None` when `other` is bigger, since there is no negative result.
+    pub fn checked_sub(&self, other: &BigUint) -> Option<BigUint> {
+        if *self < *other {
+            return None;
+        }
+        let mut limbs = self.limbs.clone();
+        sub_assign_limbs(&mut limbs, &other.limbs);
+        Some(BigUint { limbs })
+    }
+
+    pub fn div_rem(&self, other: &BigUint) -> Result<(BigUint, BigUint), DivisionByZero> {
+        if other.limbs.is_empty() {
+            return Err(DivisionByZero);
+        }
+        let (quotient, remainder) = div_rem_limbs(&self.limbs, &other.limbs);
+        Ok((
+            BigUint::from_limbs(quotient),
+            BigUint::from_limbs(remainder),
+        ))
+    }
+
+    pub(crate) fn from_limbs(mut limbs: Vec<u32>) -> Self {
+        trim_zero_limbs(&mut limbs);
+        BigUint { limbs }
+    }
+}
+
+impl From<u128> for BigUint {
+    fn from(n: u128) -> Self {
+        BigUint::from_limbs((0..4).map(|i| (n >> (32 * i)) as u32).collect())
+    }
+}
+
+macro_rules! impl_from_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BigUint {
+                fn from(n: $t) -> Self {
+                    BigUint::from(n as u128)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned!(u8, u16, u32, u64, usize);
+
+impl From<BigUint> for BigInt {
+    fn from(n: BigUint) -> Self {
+        BigInt::from_limbs(1, n.limbs)
+    }
+}
+
+impl TryFrom<BigInt> for BigUint {
+    type Error = TryFromBigIntError;
+
+    fn try_from(n: BigInt) -> Result<Self, Self::Error> {
+        if n.is_negative() {
+            return Err(TryFromBigIntError);
+        }
+        Ok(BigUint { limbs: n.limbs })
+    }
+}
+
+impl TryFrom<&BigInt> for BigUint {
+    type Error = TryFromBigIntError;
+
+    fn try_from(n: &BigInt) -> Result<Self, Self::Error> {
+        if n.is_negative() {
+            return Err(TryFromBigIntError);
+        }
+        Ok(BigUint {
+            limbs: n.limbs.clone(),
+        })
+    }
+}
+
+impl BigInt {
+    pub fn unsigned_abs(&self) -> BigUint {
+        BigUint {
+            limbs: self.limbs.clone(),
+        }
+    }
+}
+
+// Same syntax as for `BigInt`, except that a minus sign is an invalid digit.
+impl FromStr for BigUint {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.starts_with('-') {
+            return Err(ParseError::new(ParseErrorKind::InvalidDigit, 0, Some('-')));
+        }
+        let n = BigInt::from_str(s)?;
+        Ok(BigUint { limbs: n.limbs })
+    }
+}
+
+impl fmt::Display for BigUint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(true, "", &radix::magnitude_to_radix(&self.limbs, 10))
+    }
+}
+
+// The digits in groups, by default of three separated by commas, like
+// 15,688,454,648.
+pub struct Delimited<'a> {
+    biguint: &'a BigUint,
+    locale: NumberLocale,
+}
+
+impl BigUint {
+    pub fn delimited(&self) -> Delimited<'_> {
+        self.delimited_with(NumberLocale::default())
+    }
+
+    pub fn delimited_with(&self, locale: NumberLocale) -> Delimited<'_> {
+        Delimited {
+            biguint: self,
+            locale,
+        }
+    }
+
+    pub fn spelled(&self) -> Spelled<'_> {
+        self.spelled_with(Spelling::default())
+    }
+
+    pub fn spelled_with(&self, spelling: Spelling) -> Spelled<'_> {
+        Spelled::new(false, &self.limbs, spelling)
+    }
+}
+
+impl fmt::Display for Delimited<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = radix::magnitude_to_radix(&self.biguint.limbs, 10);
+        f.pad_integral(true, "", &self.locale.format(&digits))
+    }
+}
+
+impl PartialOrd for BigUint {
+    fn partial_cmp(&self, other: &BigUint) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigUint {
+    fn cmp(&self, other: &BigUint) -> Ordering {
+        cmp_limbs(&self.limbs, &other.limbs)
+    }
+}
+
+impl AddAssign<&BigUint> for BigUint {
+    fn add_assign(&mut self, other: &BigUint) {
+        add_assign_limbs(&mut self.limbs, &other.limbs);
+    }
+}
+
+impl Mul<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn mul(self, other: &BigUint) -> BigUint {
+        BigUint::from_limbs(mul_limbs(&self.limbs, &other.limbs))
+    }
+}
+
+impl Div<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn div(self, other: &BigUint) -> BigUint {
+        match self.div_rem(other) {
+            Ok((quotient, _)) => quotient,
+            Err(DivisionByZero) => panic!("attempt to divide by zero"),
+        }
+    }
+}
+
+impl Rem<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn rem(self, other: &BigUint) -> BigUint {
+        match self.div_rem(other) {
+            Ok((_, remainder)) => remainder,
+            Err(DivisionByZero) => {
+                panic!("attempt to calculate the remainder with a divisor of zero")
+            }
+        }
+    }
+}
+
+impl Add<&BigUint> for &BigUint {
+    type Output = BigUint;
+
+    fn add(self, other: &BigUint) -> BigUint {
+        let mut result = self.clone();
+        result += other;
+        result
+    }
+}
+
+// Subtraction is checked: `None` when the result would be negative, the
+// same as `checked_sub`. For that reason there is no `-=`.
+impl Sub<&BigUint> for &BigUint {
+    type Output = Option<BigUint>;
+
+    fn sub(self, other: &BigUint) -> Option<BigUint> {
+        self.checked_sub(other)
+    }
+}
+
+impl Sub<&BigUint> for BigUint {
+    type Output = Option<BigUint>;
+
+    fn sub(mut self, other: &BigUint) -> Option<BigUint> {
+        if self < *other {
+            return None;
+        }
+        sub_assign_limbs(&mut self.limbs, &other.limbs);
+        Some(self)
+    }
+}
+
+impl Sub for BigUint {
+    type Output = Option<BigUint>;
+
+    fn sub(self, other: BigUint) -> Option<BigUint> {
+        self - &other
+    }
+}
+
+impl Sub<BigUint> for &BigUint {
+    type Output = Option<BigUint>;
+
+    fn sub(self, other: BigUint) -> Option<BigUint> {
+        self.checked_sub(&other)
+    }
+}
+
+macro_rules! forward_biguint_binop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl $imp for BigUint {
+            type Output = BigUint;
+
+            fn $method(mut self, other: BigUint) -> BigUint {
+                self.$method_assign(&other);
+                self
+            }
+        }
+
+        impl $imp<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(mut self, other: &BigUint) -> BigUint {
+                self.$method_assign(other);
+                self
+            }
+        }
+
+        impl $imp<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+
+        impl $imp_assign for BigUint {
+            fn $method_assign(&mut self, other: BigUint) {
+                self.$method_assign(&other);
+            }
+        }
+    };
+}
+
+forward_biguint_binop!(Add, add, AddAssign, add_assign);
+
+// Multiplication and division build a new buffer anyway, so everything goes
+// through the borrowing versions.
+macro_rules! forward_biguint_to_ref {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident) => {
+        impl $imp for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigUint> for BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: &BigUint) -> BigUint {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<BigUint> for &BigUint {
+            type Output = BigUint;
+
+            fn $method(self, other: BigUint) -> BigUint {
+                self.$method(&other)
+            }
+        }
+
+        impl $imp_assign<&BigUint> for BigUint {
+            fn $method_assign(&mut self, other: &BigUint) {
+                *self = (&*self).$method(other);
+            }
+        }
+
+        impl $imp_assign for BigUint {
+            fn $method_assign(&mut self, other: BigUint) {
+                *self = (&*self).$method(&other);
+            }
+        }
+    };
+}
+
+forward_biguint_to_ref!(Mul, mul, MulAssign, mul_assign);
+forward_biguint_to_ref!(Div, div, DivAssign, div_assign);
+forward_biguint_to_ref!(Rem, rem, RemAssign, rem_assign);
+
+#[cfg(test)]
+mod tests {
+    use crate::{next_random, BigInt, BigUint, ParseErrorKind, TryFromBigIntError};
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    fn biguint(s: &str) -> BigUint {
+        BigUint::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn basic_display() {
+        let bi = BigUint::from_str("1000").unwrap();
+        assert_eq!(format!("{}", bi), "1000");
+        let bi = BigUint::from_str("0").unwrap();
+        assert_eq!(format!("{}", bi), "0");
+        let bi = BigUint::from_str("15688454648").unwrap();
+        assert_eq!(format!("{}", bi), "15688454648");
+        let bi = BigUint::from_str("15688454648").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "15,688,454,648");
+        let bi = BigUint::from_str("0").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "0");
+        let bi = BigUint::from_str("10").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "10");
+        let bi = BigUint::from_str("1000").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "1,000");
+        let bi = BigUint::from_str("10000").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "10,000");
+        let bi = BigUint::from_str("100000000000").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "100,000,000,000");
+        let bi = BigUint::from_str("10000000").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "10,000,000");
+        let bi = BigUint::from_str("1").unwrap();
+        assert_eq!(format!("{}", bi.delimited()), "1");
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(biguint("007"), biguint("7"));
+        assert_eq!(biguint("+0x10"), BigUint::from(16u8));
+        assert_eq!(
+            format!("{:>8}", biguint("1234567").delimited()),
+            "1,234,567"
+        );
+        assert_eq!(
+            format!("{:>10}", biguint("1234567").delimited()),
+            " 1,234,567"
+        );
+
+        let err = BigUint::from_str("-1").unwrap_err();
+        assert_eq!(
+            (err.kind(), err.position()),
+            (ParseErrorKind::InvalidDigit, 0)
+        );
+        let err = BigUint::from_str("12x").unwrap_err();
+        assert_eq!(
+            (err.kind(), err.position()),
+            (ParseErrorKind::InvalidDigit, 2)
+        );
+        assert_eq!(BigUint::from_str("").unwrap(), BigUint::zero());
+    }
+
+    #[test]
+    fn test_add_sub_ord() {
+        let a = biguint("18446744073709551615");
+        let b = biguint("1");
+        assert_eq!(&a + &b, biguint("18446744073709551616"));
+        assert_eq!((&a + &b) - &b, Some(a.clone()));
+        assert_eq!(a.clone() - a.clone(), Some(BigUint::zero()));
+        assert_eq!(&a - b.clone(), biguint("18446744073709551614").into());
+
+        let mut c = BigUint::one();
+        c += a.clone();
+        assert_eq!(c - &b, Some(a));
+
+        assert!(biguint("99") < biguint("100"));
+        assert!(biguint("4294967296") > biguint("4294967295"));
+        assert_eq!(biguint("0"), BigUint::default());
+    }
+
+    #[test]
+    fn test_sub_underflow() {
+        let (five, six) = (biguint("5"), biguint("6"));
+        assert_eq!(&five - &six, None);
+        assert_eq!(&five - six.clone(), None);
+        assert_eq!(five.clone() - &six, None);
+        assert_eq!(five - six, None);
+        assert_eq!(BigUint::zero() - BigUint::one(), None);
+    }
+
+    #[test]
+    fn test_checked_sub() {
+        assert_eq!(biguint("10").checked_sub(&biguint("3")), Some(biguint("7")));
+        assert_eq!(
+            biguint("10").checked_sub(&biguint("10")),
+            Some(BigUint::zero())
+        );
+        assert_eq!(biguint("10").checked_sub(&biguint("11")), None);
+        assert_eq!(
+            biguint("18446744073709551616").checked_sub(&biguint("1")),
+            Some(biguint("18446744073709551615"))
+        );
+        assert_eq!(BigUint::zero().checked_sub(&BigUint::one()), None);
+    }
+
+    #[test]
+    fn test_mul_div_rem() {
+        let a = biguint("123456789012345678901234567890");
+        let b = biguint("987654321");
+        assert_eq!(&a * &b, biguint("121932631124828532112482853211126352690"));
+        assert_eq!(&a / &b, biguint("124999998873437499901"));
+        assert_eq!(&a % &b, biguint("574845669"));
+        assert_eq!((&a / &b) * &b + &a % &b, a);
+        assert_eq!(a.div_rem(&b).unwrap(), (&a / &b, &a % &b));
+        assert!(a.div_rem(&BigUint::zero()).is_err());
+
+        let mut c = a.clone();
+        c *= &b;
+        c /= b.clone();
+        assert_eq!(c, a);
+        c %= biguint("1000");
+        assert_eq!(c, biguint("890"));
+        assert_eq!(BigUint::zero() * a.clone(), BigUint::zero());
+        assert_eq!(biguint("7") / biguint("8"), BigUint::zero());
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_div_by_zero() {
+        let _ = biguint("5") / BigUint::zero();
+    }
+
+    #[test]
+    fn test_matches_signed() {
+        let mut seed = 5u64;
+        let mut next = || next_random(&mut seed);
+        for _ in 0..200 {
+            let a = BigUint::from(next()) * BigUint::from(next()) * BigUint::from(next());
+            let b = BigUint::from(next() >> (next() % 64)) + BigUint::one();
+            let (sa, sb) = (BigInt::from(a.clone()), BigInt::from(b.clone()));
+
+            assert_eq!(BigInt::from(&a + &b), &sa + &sb);
+            assert_eq!(BigInt::from(&a * &b), &sa * &sb);
+            assert_eq!(BigInt::from(&a / &b), &sa / &sb);
+            assert_eq!(BigInt::from(&a % &b), &sa % &sb);
+            assert_eq!(a.cmp(&b), sa.cmp(&sb));
+            assert_eq!(
+                a.checked_sub(&b).map(BigInt::from),
+                Some(&sa - &sb).filter(|d| !d.is_negative())
+            );
+        }
+    }
+
+    #[test]
+    fn test_conversions() {
+        let n = biguint("123456789012345678901234567890");
+        let signed = BigInt::from(n.clone());
+        assert_eq!(signed.to_string(), "123456789012345678901234567890");
+        assert_eq!(BigUint::try_from(&signed), Ok(n.clone()));
+        assert_eq!(BigUint::try_from(-&signed), Err(TryFromBigIntError));
+        assert_eq!((-&signed).unsigned_abs(), n);
+        assert_eq!(BigUint::try_from(BigInt::zero()), Ok(BigUint::zero()));
+    }
+}
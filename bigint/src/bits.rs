@@ -0,0 +1,369 @@
+// Bitwise operations treat a `BigInt` as if it were stored in two's
+// complement with infinitely many sign bits, the same way Python's `int` does.
+// So -1 has every bit set, !x is -x - 1 and >> rounds towards negative infinity.
+
+use crate::{shl_limbs, shr_limbs, sub_assign_limbs, trim_zero_limbs, BigInt, LIMB_BITS};
+use std::ops::{
+    BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Not, Shl, ShlAssign, Shr,
+    ShrAssign,
+};
+
+// The lowest `len` limbs of the two's complement form of `x`.
+pub(crate) fn to_twos_complement(x: &BigInt, len: usize) -> Vec<u32> {
+    let mut limbs = x.limbs.clone();
+    limbs.resize(len, 0);
+
+    if x.is_negative() {
+        let mut carry = true;
+        for limb in limbs.iter_mut() {
+            let (value, overflow) = (!*limb).overflowing_add(carry as u32);
+            *limb = value;
+            carry = overflow;
+        }
+    }
+    limbs
+}
+
+// The top bit of the last limb is taken as the sign bit.
+pub(crate) fn from_twos_complement(mut limbs: Vec<u32>) -> BigInt {
+    let negative = limbs.last().is_some_and(|&top| top >> (LIMB_BITS - 1) == 1);
+
+    if negative {
+        let mut carry = true;
+        for limb in limbs.iter_mut() {
+            let (value, overflow) = (!*limb).overflowing_add(carry as u32);
+            *limb = value;
+            carry = overflow;
+        }
+        BigInt::from_limbs(-1, limbs)
+    } else {
+        BigInt::from_limbs(1, limbs)
+    }
+}
+
+fn bitwise(a: &BigInt, b: &BigInt, op: impl Fn(u32, u32) -> u32) -> BigInt {
+    // One extra limb makes sure both operands have a sign bit.
+    let len = a.limbs.len().max(b.limbs.len()) + 1;
+    let a = to_twos_complement(a, len);
+    let b = to_twos_complement(b, len);
+    from_twos_complement(a.iter().zip(&b).map(|(&x, &y)| op(x, y)).collect())
+}
+
+impl BigInt {
+    // Number of bits needed to represent the magnitude, 0 for zero.
+    pub fn bit_len(&self) -> u64 {
+        match self.limbs.last() {
+            None => 0,
+            Some(top) => self.limbs.len() as u64 * LIMB_BITS as u64 - top.leading_zeros() as u64,
+        }
+    }
+
+    // Number of ones in the magnitude, since a negative number has infinitely
+    // many of them in two's complement.
+    pub fn count_ones(&self) -> u64 {
+        self.limbs.iter().map(|limb| limb.count_ones() as u64).sum()
+    }
+
+    // The same for x and -x. Zero has no set bits, so it gives `None`.
+    pub fn trailing_zeros(&self) -> Option<u64> {
+        let zeros = self.limbs.iter().take_while(|&&limb| limb == 0).count();
+        self.limbs
+            .get(zeros)
+            .map(|limb| zeros as u64 * LIMB_BITS as u64 + limb.trailing_zeros() as u64)
+    }
+
+    pub fn test_bit(&self, bit: u64) -> bool {
+        let magnitude_bit = |bit: u64| {
+            let limb = (bit / LIMB_BITS as u64) as usize;
+            self.limbs
+                .get(limb)
+                .is_some_and(|&x| (x >> (bit % LIMB_BITS as u64)) & 1 == 1)
+        };
+
+        match self.trailing_zeros() {
+            Some(zeros) if self.is_negative() => {
+                // -m is !m + 1, so the bits below the lowest set bit of m stay
+                // zero, that bit stays one and all the rest get flipped.
+                bit == zeros || (bit > zeros && !magnitude_bit(bit))
+            }
+            _ => magnitude_bit(bit),
+        }
+    }
+
+    pub fn set_bit(&mut self, bit: u64, value: bool) {
+        if self.is_negative() {
+            let mask = BigInt::from(1u8) << bit as usize;
+            if value {
+                *self |= mask;
+            } else {
+                *self &= !mask;
+            }
+            return;
+        }
+
+        let limb = (bit / LIMB_BITS as u64) as usize;
+        let mask = 1 << (bit % LIMB_BITS as u64);
+        if value {
+            if self.limbs.len() <= limb {
+                self.limbs.resize(limb + 1, 0);
+            }
+            self.limbs[limb] |= mask;
+        } else if let Some(x) = self.limbs.get_mut(limb) {
+            *x &= !mask;
+            trim_zero_limbs(&mut self.limbs);
+        }
+        self.debug_check();
+    }
+}
+
+macro_rules! impl_bitwise_op {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident, $op:tt) => {
+        impl $imp<&BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                bitwise(self, other, |x, y| x $op y)
+            }
+        }
+
+        impl $imp for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                self.$method(&other)
+            }
+        }
+
+        impl $imp_assign<&BigInt> for BigInt {
+            fn $method_assign(&mut self, other: &BigInt) {
+                *self = (&*self).$method(other);
+            }
+        }
+
+        impl $imp_assign for BigInt {
+            fn $method_assign(&mut self, other: BigInt) {
+                *self = (&*self).$method(&other);
+            }
+        }
+    };
+}
+
+impl_bitwise_op!(BitAnd, bitand, BitAndAssign, bitand_assign, &);
+impl_bitwise_op!(BitOr, bitor, BitOrAssign, bitor_assign, |);
+impl_bitwise_op!(BitXor, bitxor, BitXorAssign, bitxor_assign, ^);
+
+impl Not for BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        -self - 1u32
+    }
+}
+
+impl Not for &BigInt {
+    type Output = BigInt;
+
+    fn not(self) -> BigInt {
+        -self - 1u32
+    }
+}
+
+impl Shl<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shl(self, shift: usize) -> BigInt {
+        if self.limbs.is_empty() {
+            return BigInt::new();
+        }
+
+        let mut limbs = vec![0; shift / LIMB_BITS as usize];
+        limbs.extend(shl_limbs(&self.limbs, (shift % LIMB_BITS as usize) as u32));
+        BigInt::from_limbs(self.sign, limbs)
+    }
+}
+
+impl Shr<usize> for &BigInt {
+    type Output = BigInt;
+
+    fn shr(self, shift: usize) -> BigInt {
+        let shift_magnitude = |limbs: &[u32]| {
+            let skip = (shift / LIMB_BITS as usize).min(limbs.len());
+            shr_limbs(&limbs[skip..], (shift % LIMB_BITS as usize) as u32)
+        };
+
+        if self.is_negative() {
+            // Rounds towards negative infinity: -m >> n == -(((m - 1) >> n) + 1)
+            let mut m = self.limbs.clone();
+            sub_assign_limbs(&mut m, &[1]);
+            -(BigInt::from_limbs(1, shift_magnitude(&m)) + 1u32)
+        } else {
+            BigInt::from_limbs(1, shift_magnitude(&self.limbs))
+        }
+    }
+}
+
+impl Shl<usize> for BigInt {
+    type Output = BigInt;
+
+    fn shl(self, shift: usize) -> BigInt {
+        &self << shift
+    }
+}
+
+impl Shr<usize> for BigInt {
+    type Output = BigInt;
+
+    fn shr(self, shift: usize) -> BigInt {
+        &self >> shift
+    }
+}
+
+impl ShlAssign<usize> for BigInt {
+    fn shl_assign(&mut self, shift: usize) {
+        *self = &*self << shift;
+    }
+}
+
+impl ShrAssign<usize> for BigInt {
+    fn shr_assign(&mut self, shift: usize) {
+        *self = &*self >> shift;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BigInt;
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    const SAMPLES: [i128; 14] = [
+        0,
+        1,
+        -1,
+        2,
+        -2,
+        5,
+        -5,
+        0xFFFF_FFFF,
+        -0xFFFF_FFFF,
+        0x1_0000_0000,
+        -0x1_0000_0000,
+        -123456789123456789,
+        i64::MAX as i128,
+        i64::MIN as i128,
+    ];
+
+    #[test]
+    fn test_bitwise_matches_i128() {
+        for &a in SAMPLES.iter() {
+            for &b in SAMPLES.iter() {
+                let (x, y) = (BigInt::from(a), BigInt::from(b));
+                assert_eq!(&x & &y, BigInt::from(a & b));
+                assert_eq!(&x | &y, BigInt::from(a | b));
+                assert_eq!(&x ^ &y, BigInt::from(a ^ b));
+            }
+
+            let x = BigInt::from(a);
+            assert_eq!(!&x, BigInt::from(!a));
+            for shift in [0, 1, 3, 31, 32, 33, 63].iter() {
+                assert_eq!(&x << *shift, BigInt::from(a << shift));
+                assert_eq!(&x >> *shift, BigInt::from(a >> shift));
+            }
+            for bit in 0..127 {
+                assert_eq!(x.test_bit(bit), (a >> bit) & 1 == 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitwise_big() {
+        let x = bigint("-340282366920938463463374607431768211456");
+        assert_eq!(&x >> 128, bigint("-1"));
+        assert_eq!(&x >> 129, bigint("-1"));
+        assert_eq!(&x >> 1000, bigint("-1"));
+        assert_eq!(bigint("12345") >> 1000, BigInt::new());
+        assert_eq!(bigint("1") << 128, -x);
+        assert_eq!(bigint("-1") << 100 >> 100, bigint("-1"));
+        assert_eq!(
+            bigint("-1") & bigint("0xffffffffffffffffffffffffffffffffff"),
+            bigint("0xffffffffffffffffffffffffffffffffff")
+        );
+        assert_eq!(
+            bigint("-0x10000000000000000000000000") | bigint("0xff"),
+            bigint("-0xfffffffffffffffffffffff01")
+        );
+        assert_eq!(!BigInt::new(), bigint("-1"));
+        assert_eq!(!bigint("-1"), BigInt::new());
+
+        let mut y = bigint("0xf0f0");
+        y ^= bigint("0xffff");
+        y |= &bigint("0x10000");
+        y &= bigint("0x1ff00");
+        y <<= 4;
+        y >>= 8;
+        assert_eq!(y, bigint("0x10f0"));
+    }
+
+    #[test]
+    fn test_bit_queries() {
+        assert_eq!(BigInt::new().bit_len(), 0);
+        assert_eq!(bigint("1").bit_len(), 1);
+        assert_eq!(bigint("-255").bit_len(), 8);
+        assert_eq!(bigint("0x100000000").bit_len(), 33);
+
+        assert_eq!(BigInt::new().count_ones(), 0);
+        assert_eq!(bigint("-0xff00ff00ff").count_ones(), 24);
+
+        assert_eq!(BigInt::new().trailing_zeros(), None);
+        assert_eq!(bigint("1").trailing_zeros(), Some(0));
+        assert_eq!(bigint("-12").trailing_zeros(), Some(2));
+        assert_eq!((bigint("1") << 100).trailing_zeros(), Some(100));
+    }
+
+    #[test]
+    fn test_set_bit() {
+        let mut x = BigInt::new();
+        x.set_bit(100, true);
+        assert_eq!(x, bigint("1") << 100);
+        x.set_bit(0, true);
+        x.set_bit(100, false);
+        assert_eq!(x, bigint("1"));
+        x.set_bit(0, false);
+        assert_eq!(x, BigInt::new());
+        x.set_bit(1000, false);
+        assert_eq!(x, BigInt::new());
+
+        for &a in SAMPLES.iter() {
+            for bit in [0, 1, 7, 32, 62, 100].iter() {
+                let mut x = BigInt::from(a);
+                x.set_bit(*bit, true);
+                assert_eq!(x, BigInt::from(a | 1 << bit));
+                assert!(x.test_bit(*bit));
+
+                let mut x = BigInt::from(a);
+                x.set_bit(*bit, false);
+                assert_eq!(x, BigInt::from(a & !(1 << bit)));
+                assert!(!x.test_bit(*bit));
+            }
+        }
+    }
+}
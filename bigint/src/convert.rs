@@ -0,0 +1,238 @@
+use crate::BigInt;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromBigIntError;
+
+impl fmt::Display for TryFromBigIntError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "number out of range for the target integer type")
+    }
+}
+
+impl std::error::Error for TryFromBigIntError {}
+
+impl From<u128> for BigInt {
+    fn from(n: u128) -> Self {
+        let limbs = (0..4).map(|i| (n >> (32 * i)) as u32).collect();
+        BigInt::from_limbs(1, limbs)
+    }
+}
+
+impl From<i128> for BigInt {
+    fn from(n: i128) -> Self {
+        let sign = if n < 0 { -1 } else { 1 };
+        BigInt::from_limbs(sign, BigInt::from(n.unsigned_abs()).limbs)
+    }
+}
+
+impl BigInt {
+    fn magnitude_u128(&self) -> Option<u128> {
+        if self.limbs.len() > 4 {
+            return None;
+        }
+        Some(
+            self.limbs
+                .iter()
+                .rev()
+                .fold(0, |acc, &limb| (acc << 32) | limb as u128),
+        )
+    }
+}
+
+impl TryFrom<&BigInt> for u128 {
+    type Error = TryFromBigIntError;
+
+    fn try_from(n: &BigInt) -> Result<Self, Self::Error> {
+        if n.is_negative() {
+            return Err(TryFromBigIntError);
+        }
+        n.magnitude_u128().ok_or(TryFromBigIntError)
+    }
+}
+
+impl TryFrom<&BigInt> for i128 {
+    type Error = TryFromBigIntError;
+
+    fn try_from(n: &BigInt) -> Result<Self, Self::Error> {
+        let magnitude = n.magnitude_u128().ok_or(TryFromBigIntError)?;
+        if n.is_negative() {
+            // i128::MIN has no positive counterpart, so it has to be
+            // checked against the magnitude before negating.
+            if magnitude > i128::MIN.unsigned_abs() {
+                return Err(TryFromBigIntError);
+            }
+            Ok((magnitude as i128).wrapping_neg())
+        } else {
+            i128::try_from(magnitude).map_err(|_| TryFromBigIntError)
+        }
+    }
+}
+
+macro_rules! impl_primitive_conversions {
+    ($via:ty; $($t:ty),*) => {$(
+        impl From<$t> for BigInt {
+            fn from(n: $t) -> Self {
+                BigInt::from(n as $via)
+            }
+        }
+
+        impl TryFrom<&BigInt> for $t {
+            type Error = TryFromBigIntError;
+
+            fn try_from(n: &BigInt) -> Result<Self, Self::Error> {
+                let n = <$via>::try_from(n)?;
+                <$t>::try_from(n).map_err(|_| TryFromBigIntError)
+            }
+        }
+    )*};
+}
+
+impl_primitive_conversions!(u128; u8, u16, u32, u64, usize);
+impl_primitive_conversions!(i128; i8, i16, i32, i64, isize);
+
+// Mixed arithmetic converts the primitive operand and then reuses the
+// `BigInt` operators.
+macro_rules! impl_primitive_binop {
+    ($imp:ident, $method:ident, $imp_assign:ident, $method_assign:ident; $($t:ty),*) => {$(
+        impl $imp<$t> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: $t) -> BigInt {
+                self.$method(&BigInt::from(other))
+            }
+        }
+
+        impl $imp<$t> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: $t) -> BigInt {
+                self.$method(&BigInt::from(other))
+            }
+        }
+
+        impl $imp<BigInt> for $t {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                BigInt::from(self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigInt> for $t {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                BigInt::from(self).$method(other)
+            }
+        }
+
+        impl $imp_assign<$t> for BigInt {
+            fn $method_assign(&mut self, other: $t) {
+                self.$method_assign(&BigInt::from(other));
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_primitive_ops {
+    ($($t:ty),*) => {
+        impl_primitive_binop!(Add, add, AddAssign, add_assign; $($t),*);
+        impl_primitive_binop!(Sub, sub, SubAssign, sub_assign; $($t),*);
+        impl_primitive_binop!(Mul, mul, MulAssign, mul_assign; $($t),*);
+        impl_primitive_binop!(Div, div, DivAssign, div_assign; $($t),*);
+        impl_primitive_binop!(Rem, rem, RemAssign, rem_assign; $($t),*);
+    };
+}
+
+impl_primitive_ops!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cfg(test)]
+mod tests {
+    use crate::{BigInt, TryFromBigIntError};
+    use std::convert::TryFrom;
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    macro_rules! check_boundaries {
+        ($($t:ty),*) => {$(
+            for &n in [<$t>::MIN, <$t>::MIN + 1, 0, 1, <$t>::MAX - 1, <$t>::MAX].iter() {
+                let big = BigInt::from(n);
+                assert_eq!(big, bigint(&n.to_string()));
+                assert_eq!(<$t>::try_from(&big), Ok(n));
+            }
+
+            let below = BigInt::from(<$t>::MIN) - 1;
+            let above = BigInt::from(<$t>::MAX) + 1;
+            assert_eq!(<$t>::try_from(&below), Err(TryFromBigIntError));
+            assert_eq!(<$t>::try_from(&above), Err(TryFromBigIntError));
+        )*};
+    }
+
+    #[test]
+    fn test_primitive_boundaries() {
+        check_boundaries!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+    }
+
+    #[test]
+    fn test_from_primitive() {
+        assert_eq!(BigInt::from(0u8), BigInt::new());
+        assert_eq!(BigInt::from(-0i64), BigInt::new());
+        assert!(!BigInt::from(0i128).is_negative());
+        assert_eq!(
+            BigInt::from(i128::MIN),
+            bigint("-170141183460469231731687303715884105728")
+        );
+        assert_eq!(
+            BigInt::from(u128::MAX),
+            bigint("340282366920938463463374607431768211455")
+        );
+    }
+
+    #[test]
+    fn test_try_from_bigint() {
+        assert_eq!(u64::try_from(&bigint("-1")), Err(TryFromBigIntError));
+        assert_eq!(u128::try_from(&bigint("-0")), Ok(0));
+        assert_eq!(i8::try_from(&bigint("-128")), Ok(-128));
+        assert_eq!(i8::try_from(&bigint("128")), Err(TryFromBigIntError));
+        assert_eq!(
+            i128::try_from(&bigint("-170141183460469231731687303715884105729")),
+            Err(TryFromBigIntError)
+        );
+        assert_eq!(
+            u128::try_from(&bigint("340282366920938463463374607431768211456")),
+            Err(TryFromBigIntError)
+        );
+        assert_eq!(
+            i64::try_from(&bigint("123456789012345678901234567890")),
+            Err(TryFromBigIntError)
+        );
+    }
+
+    #[test]
+    fn test_mixed_ops() {
+        let mut counter = bigint("18446744073709551615");
+        counter += 1u8;
+        assert_eq!(counter, bigint("18446744073709551616"));
+        counter -= i64::MAX;
+        assert_eq!(counter, bigint("9223372036854775809"));
+        counter *= -2i32;
+        assert_eq!(counter, bigint("-18446744073709551618"));
+        counter /= 3u64;
+        assert_eq!(counter, bigint("-6148914691236517206"));
+        counter %= 1000i16;
+        assert_eq!(counter, bigint("-206"));
+
+        assert_eq!(bigint("10") + 5i64, bigint("15"));
+        assert_eq!(&bigint("10") - 15u32, bigint("-5"));
+        assert_eq!(7i8 * bigint("-6"), bigint("-42"));
+        assert_eq!(100u128 / &bigint("7"), bigint("14"));
+        assert_eq!(-100isize % bigint("7"), bigint("-2"));
+        assert_eq!(i128::MIN + BigInt::from(i128::MAX), bigint("-1"));
+    }
+}
@@ -0,0 +1,647 @@
+// Arbitrary precision decimals. A `BigDecimal` is `unscaled * 10^-scale`, so
+// 12.50 is 1250 with scale 2 and 1.2e5 is 12 with scale -4. Values keep the
+// scale they were built with; equality and ordering compare numerically.
+
+use crate::{radix, BigInt, DivisionByZero, ParseError, ParseErrorKind};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Clone)]
+pub struct BigDecimal {
+    unscaled: BigInt,
+    scale: i64,
+}
+
+// How to drop digits that don't fit in the requested scale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RoundingMode {
+    // To the nearest value, ties to the even neighbour.
+    HalfEven,
+    // To the nearest value, ties away from zero.
+    HalfUp,
+    // Towards zero.
+    Down,
+    // Towards positive infinity.
+    Ceiling,
+    // Towards negative infinity.
+    Floor,
+}
+
+// Scales stay within this range, so that the powers of ten needed to line
+// two values up stay a sensible size. Parsing reports anything outside it as
+// OutOfRange, and building or computing such a scale panics, the way
+// primitive integer overflow does.
+const MAX_SCALE: i64 = 1_000_000;
+
+// Only called with sums and differences of a few scales in range, which
+// fit in a u32.
+fn pow10(exp: u64) -> BigInt {
+    debug_assert!(exp <= 3 * MAX_SCALE as u64);
+    BigInt::from(10u8).pow(exp as u32)
+}
+
+// numer / denom rounded to an integer, for a positive `denom`.
+fn round_div(numer: &BigInt, denom: &BigInt, mode: RoundingMode) -> BigInt {
+    let (quotient, remainder) = numer.div_rem(denom).unwrap();
+    if remainder.limbs.is_empty() {
+        return quotient;
+    }
+
+    let away_from_zero = match mode {
+        RoundingMode::Down => false,
+        RoundingMode::Ceiling => numer.is_positive(),
+        RoundingMode::Floor => numer.is_negative(),
+        RoundingMode::HalfUp | RoundingMode::HalfEven => {
+            let twice = remainder.abs_value() * 2u8;
+            match twice.cmp(denom) {
+                Ordering::Less => false,
+                Ordering::Greater => true,
+                Ordering::Equal => mode == RoundingMode::HalfUp || quotient.test_bit(0),
+            }
+        }
+    };
+
+    if !away_from_zero {
+        quotient
+    } else if numer.is_negative() {
+        quotient - 1u8
+    } else {
+        quotient + 1u8
+    }
+}
+
+fn checked_scale(scale: Option<i64>) -> i64 {
+    match scale {
+        Some(scale) if (-MAX_SCALE..=MAX_SCALE).contains(&scale) => scale,
+        _ => panic!("decimal scale overflow"),
+    }
+}
+
+impl BigDecimal {
+    pub fn new(unscaled: BigInt, scale: i64) -> Self {
+        BigDecimal {
+            unscaled,
+            scale: checked_scale(Some(scale)),
+        }
+    }
+
+    pub fn unscaled(&self) -> &BigInt {
+        &self.unscaled
+    }
+
+    pub fn scale(&self) -> i64 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.unscaled.limbs.is_empty()
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.unscaled.is_positive()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.unscaled.is_negative()
+    }
+
+    // The same value with `scale` fractional digits, rounding if that drops
+    // any.
+    pub fn with_scale(&self, scale: i64, mode: RoundingMode) -> BigDecimal {
+        let scale = checked_scale(Some(scale));
+        let unscaled = match scale.cmp(&self.scale) {
+            Ordering::Equal => self.unscaled.clone(),
+            Ordering::Greater => &self.unscaled * pow10(scale.abs_diff(self.scale)),
+            Ordering::Less => round_div(&self.unscaled, &pow10(self.scale.abs_diff(scale)), mode),
+        };
+        BigDecimal { unscaled, scale }
+    }
+
+    // Keeps `digits` significant digits, which must be at least one.
+    pub fn with_precision(&self, digits: u64, mode: RoundingMode) -> BigDecimal {
+        assert!(digits > 0, "precision must be at least one digit");
+        let length = self.unscaled.abs_value().to_string().len() as u64;
+        if self.is_zero() || length <= digits {
+            return BigDecimal::new(self.unscaled.clone(), self.scale);
+        }
+
+        let dropped = length - digits;
+        let rounded = self.with_scale(checked_scale(self.scale.checked_sub(dropped as i64)), mode);
+        // Rounding 999 up to 1000 gains a digit.
+        if rounded.unscaled.abs_value().to_string().len() as u64 > digits {
+            return BigDecimal::new(
+                &rounded.unscaled / 10u8,
+                checked_scale(rounded.scale.checked_sub(1)),
+            );
+        }
+        rounded
+    }
+
+    // self / other with `scale` fractional digits.
+    pub fn div_with_scale(
+        &self,
+        other: &BigDecimal,
+        scale: i64,
+        mode: RoundingMode,
+    ) -> Result<BigDecimal, DivisionByZero> {
+        if other.is_zero() {
+            return Err(DivisionByZero);
+        }
+
+        // The quotient's unscaled value is a / b * 10^(scale - a.scale + b.scale).
+        let scale = checked_scale(Some(scale));
+        let shift = scale - self.scale + other.scale;
+        let (mut numer, mut denom) = if shift >= 0 {
+            (&self.unscaled * pow10(shift as u64), other.unscaled.clone())
+        } else {
+            (
+                self.unscaled.clone(),
+                &other.unscaled * pow10(shift.unsigned_abs()),
+            )
+        };
+        if denom.is_negative() {
+            numer = -numer;
+            denom = -denom;
+        }
+
+        Ok(BigDecimal {
+            unscaled: round_div(&numer, &denom, mode),
+            scale,
+        })
+    }
+
+    // Both unscaled values at the larger of the two scales.
+    fn aligned(&self, other: &BigDecimal) -> (BigInt, BigInt, i64) {
+        match self.scale.cmp(&other.scale) {
+            Ordering::Equal => (self.unscaled.clone(), other.unscaled.clone(), self.scale),
+            Ordering::Less => (
+                &self.unscaled * pow10(other.scale.abs_diff(self.scale)),
+                other.unscaled.clone(),
+                other.scale,
+            ),
+            Ordering::Greater => (
+                self.unscaled.clone(),
+                &other.unscaled * pow10(self.scale.abs_diff(other.scale)),
+                self.scale,
+            ),
+        }
+    }
+
+    // Bounds on the exponent of the leading digit, floor(log10(|self|)), of
+    // a nonzero value, worked out from the bit length alone with a digit of
+    // slack either way for rounding.
+    fn exponent_bounds(&self) -> (i64, i64) {
+        let bits = self.unscaled.bit_len() as f64;
+        let low = ((bits - 1.0) * std::f64::consts::LOG10_2).floor() as i64 - 1;
+        let high = (bits * std::f64::consts::LOG10_2).floor() as i64 + 1;
+        (low - self.scale, high - self.scale)
+    }
+}
+
+impl From<BigInt> for BigDecimal {
+    fn from(n: BigInt) -> Self {
+        BigDecimal::new(n, 0)
+    }
+}
+
+impl Default for BigDecimal {
+    fn default() -> Self {
+        BigDecimal::new(BigInt::new(), 0)
+    }
+}
+
+// Accepts an optional sign, digits with an optional fractional part and an
+// optional exponent, like "-12.50", "1e6" or "6.022E+23".
+impl FromStr for BigDecimal {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sign, rest) = radix::split_sign(s)?;
+        let offset = s.len() - rest.len();
+
+        let (mantissa, exponent) = match rest.find(['e', 'E']) {
+            Some(e) => (&rest[..e], Some(&rest[e + 1..])),
+            None => (rest, None),
+        };
+
+        let (whole, fraction) = match mantissa.find('.') {
+            Some(dot) => (&mantissa[..dot], &mantissa[dot + 1..]),
+            None => (mantissa, ""),
+        };
+        if whole.is_empty() && fraction.is_empty() {
+            return Err(ParseError::new(ParseErrorKind::Empty, offset, None));
+        }
+
+        // Where to report a scale out of range: the exponent if there is one,
+        // otherwise the point.
+        let mut point = offset + whole.len();
+        let mut scale = fraction.len() as i64;
+        if let Some(exponent) = exponent {
+            let exponent_offset = s.len() - exponent.len();
+            point = exponent_offset;
+            let value: BigInt = match radix::split_sign(exponent) {
+                Ok((exp_sign, exp_digits)) => {
+                    let magnitude =
+                        radix::parse_magnitude(exp_digits, 10, s.len() - exp_digits.len())?;
+                    BigInt::from_limbs(exp_sign, magnitude)
+                }
+                Err(err) => {
+                    return Err(ParseError::new(
+                        err.kind(),
+                        exponent_offset + err.position(),
+                        err.character(),
+                    ))
+                }
+            };
+            scale = i64::try_from(&value)
+                .ok()
+                .and_then(|e| scale.checked_sub(e))
+                .ok_or_else(|| {
+                    ParseError::new(ParseErrorKind::OutOfRange, exponent_offset, None)
+                })?;
+        }
+        if !(-MAX_SCALE..=MAX_SCALE).contains(&scale) {
+            return Err(ParseError::new(ParseErrorKind::OutOfRange, point, None));
+        }
+
+        // Digits on one side of the point are enough, as in "5." or ".5".
+        let mut digits = String::with_capacity(whole.len() + fraction.len());
+        digits.push_str(whole);
+        digits.push_str(fraction);
+        let limbs = match radix::parse_magnitude(&digits, 10, offset) {
+            Ok(limbs) => limbs,
+            Err(err) if err.position() >= offset + whole.len() => {
+                // Skip over the point when reporting a bad fractional digit.
+                return Err(ParseError::new(
+                    err.kind(),
+                    err.position() + 1,
+                    err.character(),
+                ));
+            }
+            Err(err) => return Err(err),
+        };
+
+        Ok(BigDecimal::new(BigInt::from_limbs(sign, limbs), scale))
+    }
+}
+
+// Plain notation, never an exponent.
+impl fmt::Display for BigDecimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_negative() {
+            write!(f, "-")?;
+        }
+        let digits = radix::magnitude_to_radix(&self.unscaled.limbs, 10);
+
+        if self.scale <= 0 {
+            write!(f, "{}", digits)?;
+            if !self.is_zero() {
+                for _ in 0..self.scale.unsigned_abs() {
+                    write!(f, "0")?;
+                }
+            }
+            return Ok(());
+        }
+
+        let scale = self.scale as u64;
+        if (digits.len() as u64) > scale {
+            let point = digits.len() - scale as usize;
+            write!(f, "{}.{}", &digits[..point], &digits[point..])
+        } else {
+            write!(f, "0.")?;
+            for _ in 0..scale - digits.len() as u64 {
+                write!(f, "0")?;
+            }
+            write!(f, "{}", digits)
+        }
+    }
+}
+
+impl PartialEq for BigDecimal {
+    fn eq(&self, other: &BigDecimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for BigDecimal {}
+
+impl PartialOrd for BigDecimal {
+    fn partial_cmp(&self, other: &BigDecimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigDecimal {
+    fn cmp(&self, other: &BigDecimal) -> Ordering {
+        if self.scale == other.scale {
+            return self.unscaled.cmp(&other.unscaled);
+        }
+
+        // Signs decide before scaling anything up, and then the positions
+        // of the leading digits unless they are too close to call.
+        match self.unscaled.signum().cmp(&other.unscaled.signum()) {
+            Ordering::Equal => {}
+            unequal => return unequal,
+        }
+        if self.is_zero() {
+            return Ordering::Equal;
+        }
+
+        let (low, high) = self.exponent_bounds();
+        let (other_low, other_high) = other.exponent_bounds();
+        let magnitude = if high < other_low {
+            Ordering::Less
+        } else if low > other_high {
+            Ordering::Greater
+        } else {
+            let (a, b, _) = self.aligned(other);
+            return a.cmp(&b);
+        };
+        if self.is_negative() {
+            magnitude.reverse()
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl Add<&BigDecimal> for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn add(self, other: &BigDecimal) -> BigDecimal {
+        let (a, b, scale) = self.aligned(other);
+        BigDecimal::new(a + b, scale)
+    }
+}
+
+impl Sub<&BigDecimal> for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn sub(self, other: &BigDecimal) -> BigDecimal {
+        let (a, b, scale) = self.aligned(other);
+        BigDecimal::new(a - b, scale)
+    }
+}
+
+impl Mul<&BigDecimal> for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn mul(self, other: &BigDecimal) -> BigDecimal {
+        BigDecimal::new(
+            &self.unscaled * &other.unscaled,
+            checked_scale(self.scale.checked_add(other.scale)),
+        )
+    }
+}
+
+macro_rules! forward_decimal_binop {
+    ($imp:ident, $method:ident) => {
+        impl $imp for BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: BigDecimal) -> BigDecimal {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigDecimal> for BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: &BigDecimal) -> BigDecimal {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<BigDecimal> for &BigDecimal {
+            type Output = BigDecimal;
+
+            fn $method(self, other: BigDecimal) -> BigDecimal {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_decimal_binop!(Add, add);
+forward_decimal_binop!(Sub, sub);
+forward_decimal_binop!(Mul, mul);
+
+impl Neg for BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> BigDecimal {
+        BigDecimal::new(-self.unscaled, self.scale)
+    }
+}
+
+impl Neg for &BigDecimal {
+    type Output = BigDecimal;
+
+    fn neg(self) -> BigDecimal {
+        BigDecimal::new(-&self.unscaled, self.scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BigDecimal, RoundingMode};
+    use crate::{BigInt, ParseErrorKind};
+    use std::str::FromStr;
+
+    fn decimal(s: &str) -> BigDecimal {
+        BigDecimal::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_from_str() {
+        let d = decimal("-12.50");
+        assert_eq!((d.unscaled(), d.scale()), (&BigInt::from(-1250), 2));
+        let d = decimal("6.022E+23");
+        assert_eq!((d.unscaled(), d.scale()), (&BigInt::from(6022), -20));
+        let d = decimal("1e-3");
+        assert_eq!((d.unscaled(), d.scale()), (&BigInt::from(1), 3));
+        let d = decimal(".5");
+        assert_eq!((d.unscaled(), d.scale()), (&BigInt::from(5), 1));
+        let d = decimal("+5.");
+        assert_eq!((d.unscaled(), d.scale()), (&BigInt::from(5), 0));
+        let d = decimal("-0.000");
+        assert!(d.is_zero() && !d.is_negative());
+        assert_eq!(d.scale(), 3);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let cases = [
+            ("", ParseErrorKind::Empty, 0),
+            ("-", ParseErrorKind::LoneSign, 0),
+            (".", ParseErrorKind::Empty, 0),
+            ("1.2.3", ParseErrorKind::InvalidDigit, 3),
+            ("12x", ParseErrorKind::InvalidDigit, 2),
+            ("1.5e", ParseErrorKind::Empty, 4),
+            ("1.5e-", ParseErrorKind::LoneSign, 4),
+            ("1.5e+3x", ParseErrorKind::InvalidDigit, 6),
+            ("1e99999999999999999999", ParseErrorKind::OutOfRange, 2),
+            ("1e-5000000000", ParseErrorKind::OutOfRange, 2),
+            ("-1.5e1000002", ParseErrorKind::OutOfRange, 5),
+        ];
+        for &(s, kind, position) in cases.iter() {
+            let err = BigDecimal::from_str(s).unwrap_err();
+            assert_eq!((err.kind(), err.position()), (kind, position), "{}", s);
+        }
+
+        let long_fraction = format!("12.{}", "0".repeat(1_000_001));
+        let err = BigDecimal::from_str(&long_fraction).unwrap_err();
+        assert_eq!(
+            (err.kind(), err.position()),
+            (ParseErrorKind::OutOfRange, 2)
+        );
+
+        assert_eq!(decimal("1e-1000000").scale(), 1_000_000);
+        assert_eq!(decimal("-2.5e1000001").scale(), -1_000_000);
+    }
+
+    #[test]
+    #[should_panic(expected = "decimal scale overflow")]
+    fn test_scale_out_of_range() {
+        let _ = BigDecimal::new(BigInt::from(1), i64::MAX);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(decimal("-12.50").to_string(), "-12.50");
+        assert_eq!(decimal("1.5e3").to_string(), "1500");
+        assert_eq!(decimal("-5e-3").to_string(), "-0.005");
+        assert_eq!(decimal("0.000").to_string(), "0.000");
+        assert_eq!(decimal("0e5").to_string(), "0");
+        assert_eq!(decimal("123e-3").to_string(), "0.123");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!((decimal("19.99") + decimal("0.011")).to_string(), "20.001");
+        assert_eq!((decimal("1e3") - decimal("0.5")).to_string(), "999.5");
+        assert_eq!((&decimal("-1.5") * &decimal("2.25")).to_string(), "-3.375");
+        assert_eq!((decimal("0.1") + decimal("0.2")), decimal("0.3"));
+        assert_eq!((-decimal("2.50")).to_string(), "-2.50");
+        assert_eq!((decimal("1.5e10") * decimal("2e-10")).to_string(), "3.0");
+    }
+
+    #[test]
+    fn test_order() {
+        assert_eq!(decimal("1.0"), decimal("1.000"));
+        assert_eq!(decimal("1e2"), decimal("100"));
+        assert!(decimal("-0.1") < decimal("0"));
+        assert!(decimal("1.01") > decimal("1.001"));
+        assert!(decimal("-2e1") < decimal("-19.99"));
+        assert!(decimal("1e-1000") > decimal("-1e1000"));
+
+        // Far apart enough to decide without lining the scales up.
+        assert!(decimal("1e-1000000") < decimal("1"));
+        assert!(decimal("-1e1000000") < decimal("-5"));
+        assert!(decimal("123456789e999990") > decimal("5e-1000000"));
+        assert!(decimal("9.99e999999") < decimal("1e1000000"));
+        assert!(decimal("-1000e-3") == decimal("-1"));
+        assert!(decimal("0e-1000000") == decimal("0e1000000"));
+    }
+
+    #[test]
+    fn test_rounding_modes() {
+        let inputs = [
+            "5.5", "2.5", "1.6", "1.1", "1.0", "-1.0", "-1.1", "-1.6", "-2.5", "-5.5",
+        ];
+        let table = [
+            (RoundingMode::Down, [5, 2, 1, 1, 1, -1, -1, -1, -2, -5]),
+            (RoundingMode::Ceiling, [6, 3, 2, 2, 1, -1, -1, -1, -2, -5]),
+            (RoundingMode::Floor, [5, 2, 1, 1, 1, -1, -2, -2, -3, -6]),
+            (RoundingMode::HalfUp, [6, 3, 2, 1, 1, -1, -1, -2, -3, -6]),
+            (RoundingMode::HalfEven, [6, 2, 2, 1, 1, -1, -1, -2, -2, -6]),
+        ];
+        for &(mode, expected) in table.iter() {
+            for (input, &value) in inputs.iter().zip(expected.iter()) {
+                let rounded = decimal(input).with_scale(0, mode);
+                assert_eq!(
+                    rounded.unscaled(),
+                    &BigInt::from(value),
+                    "{} {:?}",
+                    input,
+                    mode
+                );
+                assert_eq!(rounded.scale(), 0);
+            }
+        }
+
+        let d = decimal("1234.5678");
+        assert_eq!(
+            d.with_scale(2, RoundingMode::HalfEven).to_string(),
+            "1234.57"
+        );
+        assert_eq!(d.with_scale(-2, RoundingMode::HalfEven).to_string(), "1200");
+        assert_eq!(
+            d.with_scale(6, RoundingMode::Down).to_string(),
+            "1234.567800"
+        );
+        assert_eq!(
+            decimal("0.125")
+                .with_scale(2, RoundingMode::HalfEven)
+                .to_string(),
+            "0.12"
+        );
+        assert_eq!(
+            decimal("0.135")
+                .with_scale(2, RoundingMode::HalfEven)
+                .to_string(),
+            "0.14"
+        );
+    }
+
+    #[test]
+    fn test_precision() {
+        let d = decimal("1234.5678");
+        assert_eq!(d.with_precision(3, RoundingMode::HalfUp), decimal("1.23e3"));
+        assert_eq!(
+            d.with_precision(6, RoundingMode::Floor).to_string(),
+            "1234.56"
+        );
+        assert_eq!(
+            d.with_precision(10, RoundingMode::Floor).to_string(),
+            "1234.5678"
+        );
+
+        let d = decimal("-99.96").with_precision(3, RoundingMode::HalfUp);
+        assert_eq!((d.unscaled(), d.scale()), (&BigInt::from(-100), 0));
+    }
+
+    #[test]
+    fn test_division() {
+        let third = decimal("1")
+            .div_with_scale(&decimal("3"), 10, RoundingMode::HalfEven)
+            .unwrap();
+        assert_eq!(third.to_string(), "0.3333333333");
+
+        let q = decimal("2")
+            .div_with_scale(&decimal("-3"), 4, RoundingMode::HalfUp)
+            .unwrap();
+        assert_eq!(q.to_string(), "-0.6667");
+        let q = decimal("2")
+            .div_with_scale(&decimal("-3"), 4, RoundingMode::Ceiling)
+            .unwrap();
+        assert_eq!(q.to_string(), "-0.6666");
+
+        // Splitting 100.00 three ways, and a negative result scale.
+        let q = decimal("100.00")
+            .div_with_scale(&decimal("3"), 2, RoundingMode::Floor)
+            .unwrap();
+        assert_eq!(q.to_string(), "33.33");
+        let q = decimal("123456")
+            .div_with_scale(&decimal("0.5e-3"), -3, RoundingMode::HalfEven)
+            .unwrap();
+        assert_eq!(q, decimal("246912e3"));
+
+        assert!(decimal("1")
+            .div_with_scale(&decimal("0.00"), 2, RoundingMode::Down)
+            .is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// Compact binary forms. The byte orders match the usual conventions:
+// big-endian magnitudes, little-endian two's complement, and zigzag LEB128
+// varints where small numbers of either sign take a single byte.
+
+use crate::bits::{from_twos_complement, to_twos_complement};
+use crate::{BigInt, LIMB_BITS};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DecodeVarintError;
+
+impl fmt::Display for DecodeVarintError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "varint ends before its last byte")
+    }
+}
+
+impl std::error::Error for DecodeVarintError {}
+
+fn limbs_from_bytes_le(bytes: impl Iterator<Item = u8>) -> Vec<u32> {
+    let mut limbs = Vec::new();
+    let mut acc = 0u32;
+    let mut shift = 0;
+    for byte in bytes {
+        acc |= (byte as u32) << shift;
+        shift += 8;
+        if shift == LIMB_BITS {
+            limbs.push(acc);
+            acc = 0;
+            shift = 0;
+        }
+    }
+    if shift > 0 {
+        limbs.push(acc);
+    }
+    limbs
+}
+
+impl BigInt {
+    // The magnitude with the most significant byte first and no leading
+    // zeros, so zero is empty. The sign is dropped.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.limbs
+            .iter()
+            .rev()
+            .flat_map(|limb| limb.to_be_bytes())
+            .skip_while(|&byte| byte == 0)
+            .collect()
+    }
+
+    // The non-negative number with these big-endian bytes. Leading zeros are
+    // fine.
+    pub fn from_bytes_be(bytes: &[u8]) -> BigInt {
+        BigInt::from_limbs(1, limbs_from_bytes_le(bytes.iter().rev().copied()))
+    }
+
+    // The shortest two's complement form, least significant byte first. Zero
+    // is empty and the top bit of the last byte is the sign.
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        let limbs = to_twos_complement(self, self.limbs.len() + 1);
+        let mut bytes: Vec<u8> = limbs.iter().flat_map(|limb| limb.to_le_bytes()).collect();
+
+        // Drop bytes that only repeat the sign bit of the byte below them.
+        while let Some(&top) = bytes.last() {
+            let below_negative = bytes.len() >= 2 && bytes[bytes.len() - 2] >= 0x80;
+            let redundant = match top {
+                0x00 => !below_negative,
+                0xff => bytes.len() >= 2 && below_negative,
+                _ => false,
+            };
+            if !redundant {
+                break;
+            }
+            bytes.pop();
+        }
+        bytes
+    }
+
+    // Reads two's complement bytes, least significant first. Extra sign bytes
+    // are fine and no bytes at all is zero.
+    pub fn from_signed_bytes_le(bytes: &[u8]) -> BigInt {
+        let sign_byte = match bytes.last() {
+            Some(&top) if top >= 0x80 => 0xff,
+            _ => 0x00,
+        };
+        let padding = (4 - bytes.len() % 4) % 4;
+        let padded = bytes
+            .iter()
+            .copied()
+            .chain(std::iter::repeat_n(sign_byte, padding));
+        from_twos_complement(limbs_from_bytes_le(padded))
+    }
+
+    // Zigzag maps 0, -1, 1, -2, ... to 0, 1, 2, 3, ... and LEB128 then
+    // stores that seven bits per byte, low bits first, with the top bit set
+    // on every byte but the last.
+    pub fn write_varint(&self, out: &mut Vec<u8>) {
+        let zigzag = if self.is_negative() {
+            (self.abs_value() << 1) - 1u8
+        } else {
+            self.abs_value() << 1
+        };
+
+        let start = out.len();
+        let mut acc = 0u64;
+        let mut acc_bits = 0;
+        for &limb in zigzag.limbs.iter() {
+            acc |= (limb as u64) << acc_bits;
+            acc_bits += LIMB_BITS;
+            while acc_bits >= 7 {
+                out.push((acc & 0x7f) as u8);
+                acc >>= 7;
+                acc_bits -= 7;
+            }
+        }
+        if acc_bits > 0 {
+            out.push(acc as u8);
+        }
+
+        // The last limb usually leaves some empty groups at the top.
+        while out.len() > start + 1 && out.last() == Some(&0) {
+            out.pop();
+        }
+        if out.len() == start {
+            out.push(0);
+        }
+        let last = out.len() - 1;
+        for byte in out[start..last].iter_mut() {
+            *byte |= 0x80;
+        }
+    }
+
+    pub fn to_varint(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_varint(&mut out);
+        out
+    }
+
+    // Decodes the varint at the start of `bytes` and returns it together
+    // with the number of bytes it took, so that varints can be read back to
+    // back.
+    pub fn from_varint(bytes: &[u8]) -> Result<(BigInt, usize), DecodeVarintError> {
+        let mut limbs = Vec::new();
+        let mut acc = 0u64;
+        let mut acc_bits = 0;
+
+        for (i, &byte) in bytes.iter().enumerate() {
+            acc |= ((byte & 0x7f) as u64) << acc_bits;
+            acc_bits += 7;
+            if acc_bits >= LIMB_BITS {
+                limbs.push(acc as u32);
+                acc >>= LIMB_BITS;
+                acc_bits -= LIMB_BITS;
+            }
+
+            if byte & 0x80 == 0 {
+                limbs.push(acc as u32);
+                let zigzag = BigInt::from_limbs(1, limbs);
+                let value = if zigzag.test_bit(0) {
+                    -((zigzag + 1u8) >> 1)
+                } else {
+                    zigzag >> 1
+                };
+                return Ok((value, i + 1));
+            }
+        }
+        Err(DecodeVarintError)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DecodeVarintError;
+    use crate::{random_bigint, random_limbs, BigInt};
+
+    #[test]
+    fn test_bytes_be() {
+        assert_eq!(BigInt::new().to_bytes_be(), Vec::<u8>::new());
+        assert_eq!(BigInt::from(-258).to_bytes_be(), vec![1, 2]);
+        assert_eq!(BigInt::from(1u64 << 32).to_bytes_be(), vec![1, 0, 0, 0, 0]);
+        assert_eq!(BigInt::from_bytes_be(&[0, 0, 1, 2]), BigInt::from(258));
+        assert_eq!(BigInt::from_bytes_be(&[]), BigInt::new());
+
+        let mut seed = 1u64;
+        for shift in 0..200 {
+            let limbs = random_limbs(&mut seed, 4);
+            let n = limbs.iter().rev().fold(0, |n, &l| n << 32 | l as u128) >> (shift % 128);
+            let expected: Vec<u8> = n
+                .to_be_bytes()
+                .iter()
+                .copied()
+                .skip_while(|&b| b == 0)
+                .collect();
+            assert_eq!(BigInt::from(n).to_bytes_be(), expected);
+        }
+    }
+
+    #[test]
+    fn test_signed_bytes_le() {
+        let cases: [(i64, &[u8]); 9] = [
+            (0, &[]),
+            (-1, &[0xff]),
+            (127, &[0x7f]),
+            (128, &[0x80, 0x00]),
+            (255, &[0xff, 0x00]),
+            (-128, &[0x80]),
+            (-129, &[0x7f, 0xff]),
+            (-32768, &[0x00, 0x80]),
+            (i64::MIN, &[0, 0, 0, 0, 0, 0, 0, 0x80]),
+        ];
+        for &(n, bytes) in cases.iter() {
+            assert_eq!(BigInt::from(n).to_signed_bytes_le(), bytes, "{}", n);
+            assert_eq!(BigInt::from_signed_bytes_le(bytes), BigInt::from(n));
+        }
+
+        // Sign extension doesn't change the value.
+        assert_eq!(
+            BigInt::from_signed_bytes_le(&[0x7f, 0xff, 0xff, 0xff, 0xff]),
+            BigInt::from(-129)
+        );
+        assert_eq!(
+            BigInt::from_signed_bytes_le(&[0x80, 0, 0, 0, 0, 0]),
+            BigInt::from(128)
+        );
+    }
+
+    #[test]
+    fn test_varint() {
+        let cases: [(i64, &[u8]); 8] = [
+            (0, &[0]),
+            (-1, &[1]),
+            (1, &[2]),
+            (63, &[0x7e]),
+            (-64, &[0x7f]),
+            (64, &[0x80, 0x01]),
+            (300, &[0xd8, 0x04]),
+            (
+                i64::MIN,
+                &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01],
+            ),
+        ];
+        for &(n, bytes) in cases.iter() {
+            assert_eq!(BigInt::from(n).to_varint(), bytes, "{}", n);
+            assert_eq!(
+                BigInt::from_varint(bytes),
+                Ok((BigInt::from(n), bytes.len()))
+            );
+        }
+
+        assert_eq!(BigInt::from_varint(&[]), Err(DecodeVarintError));
+        assert_eq!(BigInt::from_varint(&[0x80, 0x80]), Err(DecodeVarintError));
+        // Padding groups are tolerated.
+        assert_eq!(
+            BigInt::from_varint(&[0x82, 0x80, 0x00]),
+            Ok((BigInt::from(1), 3))
+        );
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let mut seed = 12345;
+        for _ in 0..2000 {
+            let n = random_bigint(&mut seed, 12);
+
+            let magnitude = BigInt::from_bytes_be(&n.to_bytes_be());
+            assert_eq!(magnitude, n.abs_value());
+
+            let signed = n.to_signed_bytes_le();
+            assert_eq!(BigInt::from_signed_bytes_le(&signed), n);
+            if !signed.is_empty() {
+                // Nothing shorter would do.
+                let top = BigInt::from_signed_bytes_le(&signed[..signed.len() - 1]);
+                assert_ne!(top, n);
+            }
+
+            let varint = n.to_varint();
+            assert!(varint.len() == 1 || *varint.last().unwrap() != 0);
+            assert_eq!(BigInt::from_varint(&varint), Ok((n, varint.len())));
+        }
+    }
+
+    #[test]
+    fn test_varint_stream() {
+        let mut seed = 99;
+        let values: Vec<BigInt> = (0..500).map(|_| random_bigint(&mut seed, 6)).collect();
+
+        let mut stream = Vec::new();
+        for n in values.iter() {
+            n.write_varint(&mut stream);
+        }
+
+        let mut rest = &stream[..];
+        for n in values.iter() {
+            let (value, used) = BigInt::from_varint(rest).unwrap();
+            assert_eq!(&value, n);
+            rest = &rest[used..];
+        }
+        assert!(rest.is_empty());
+    }
+}
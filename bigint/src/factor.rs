@@ -0,0 +1,755 @@
+// Integer factorization: trial division by the small primes, then Pollard's
+// rho with Brent's cycle detection, then Lenstra's elliptic curve method for
+// whatever is left. Both of the latter do their modular arithmetic in
+// Montgomery form directly on the limbs.
+
+use crate::prime::small_primes;
+use crate::{cmp_limbs, rem_small, sub_assign_limbs, trim_zero_limbs, BigInt, LIMB_BITS};
+use std::cell::Cell;
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+// Limits how much work `factor_with_budget` may do. One iteration is one
+// modular multiplication.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FactorBudget {
+    duration: Option<Duration>,
+    iterations: Option<u64>,
+}
+
+impl FactorBudget {
+    pub fn unlimited() -> Self {
+        FactorBudget::default()
+    }
+
+    pub fn with_duration(mut self, duration: Duration) -> Self {
+        self.duration = Some(duration);
+        self
+    }
+
+    pub fn with_iterations(mut self, iterations: u64) -> Self {
+        self.iterations = Some(iterations);
+        self
+    }
+}
+
+// Prime factors with their multiplicities, in increasing order. When the
+// budget runs out, the composite parts that could not be split are kept in
+// `unfactored`, so the product of everything is still the original number.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Factorization {
+    factors: Vec<(BigInt, u32)>,
+    unfactored: Vec<(BigInt, u32)>,
+}
+
+impl Factorization {
+    pub fn factors(&self) -> &[(BigInt, u32)] {
+        &self.factors
+    }
+
+    pub fn unfactored(&self) -> &[(BigInt, u32)] {
+        &self.unfactored
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.unfactored.is_empty()
+    }
+
+    pub fn into_factors(self) -> Vec<(BigInt, u32)> {
+        self.factors
+    }
+}
+
+// The prime factorization of `n` with multiplicities, smallest prime first.
+// Negative numbers get a factor of -1, 0 is returned as is and 1 has no
+// factors.
+//
+// There is no limit on the work done, and a product of two large primes of
+// similar size (a 200 digit semiprime, say) can keep rho and ECM busy for
+// longer than anyone will wait, so this may effectively never return. Use
+// `factor_with_budget` for any input that isn't known to be easy.
+pub fn factor(n: &BigInt) -> Vec<(BigInt, u32)> {
+    factor_with_budget(n, FactorBudget::unlimited()).into_factors()
+}
+
+pub fn factor_with_budget(n: &BigInt, budget: FactorBudget) -> Factorization {
+    let mut factorizer = Factorizer {
+        budget,
+        start: Instant::now(),
+        spent: 0,
+    };
+    factorizer.run(n)
+}
+
+// Sorts the numbers and counts the repeated ones.
+fn group(mut numbers: Vec<BigInt>) -> Vec<(BigInt, u32)> {
+    numbers.sort();
+    let mut result: Vec<(BigInt, u32)> = Vec::new();
+    for n in numbers {
+        match result.last_mut() {
+            Some((last, count)) if *last == n => *count += 1,
+            _ => result.push((n, 1)),
+        }
+    }
+    result
+}
+
+struct Factorizer {
+    budget: FactorBudget,
+    start: Instant,
+    spent: u64,
+}
+
+impl Factorizer {
+    fn exhausted(&self, context: &Montgomery) -> bool {
+        let spent = self.spent + context.multiplications.get();
+        self.budget.iterations.is_some_and(|max| spent >= max)
+            || self
+                .budget
+                .duration
+                .is_some_and(|max| self.start.elapsed() >= max)
+    }
+
+    fn run(&mut self, n: &BigInt) -> Factorization {
+        let mut primes = Vec::new();
+        let mut unfactored = Vec::new();
+
+        if n.limbs.is_empty() {
+            return Factorization {
+                factors: vec![(BigInt::new(), 1)],
+                unfactored: Vec::new(),
+            };
+        }
+        if n.is_negative() {
+            primes.push(BigInt::from(-1i8));
+        }
+
+        let mut rest = n.abs_value();
+        for &p in small_primes() {
+            while rem_small(&rest.limbs, p) == 0 {
+                rest /= p;
+                primes.push(BigInt::from(p));
+            }
+        }
+
+        let mut stack = Vec::new();
+        if rest > BigInt::from(1u8) {
+            stack.push(rest);
+        }
+
+        while let Some(m) = stack.pop() {
+            if m.is_probable_prime_bpsw() {
+                primes.push(m);
+            } else if let Some((root, k)) = perfect_power(&m) {
+                for _ in 0..k {
+                    stack.push(root.abs_value());
+                }
+            } else {
+                match self.find_factor(&m) {
+                    Some(d) => {
+                        stack.push(&m / &d);
+                        stack.push(d);
+                    }
+                    None => unfactored.push(m),
+                }
+            }
+        }
+
+        Factorization {
+            factors: group(primes),
+            unfactored: group(unfactored),
+        }
+    }
+
+    // A non-trivial factor of the odd composite `n`, unless the budget runs
+    // out first.
+    fn find_factor(&mut self, n: &BigInt) -> Option<BigInt> {
+        let context = Montgomery::new(n);
+
+        // Rho finds factors up to about ten digits quickly, ECM takes over
+        // for the bigger ones.
+        let mut found = self.pollard_brent(&context, 1, 1 << 16);
+        if found.is_none() && !self.exhausted(&context) {
+            found = self.ecm(&context);
+        }
+        self.spent += context.multiplications.get();
+        found
+    }
+
+    fn pollard_brent(&self, context: &Montgomery, c: u32, max_steps: u64) -> Option<BigInt> {
+        // Batching the differences means only one gcd every `BATCH` steps.
+        const BATCH: u64 = 128;
+
+        let c = context.to_montgomery(&BigInt::from(c));
+        let f = |x: &[u32]| context.add(&context.mul(x, x), &c);
+
+        let mut y = context.to_montgomery(&BigInt::from(2u8));
+        let mut x = y.clone();
+        let mut saved_y = y.clone();
+        let mut q = context.one.clone();
+        let mut g = BigInt::from(1u8);
+        let mut r = 1u64;
+
+        while g == BigInt::from(1u8) {
+            x = y.clone();
+            for _ in 0..r {
+                y = f(&y);
+            }
+
+            let mut k = 0;
+            while k < r && g == BigInt::from(1u8) {
+                saved_y = y.clone();
+                for _ in 0..BATCH.min(r - k) {
+                    y = f(&y);
+                    q = context.mul(&q, &context.sub(&x, &y));
+                }
+                g = context.gcd(&q);
+                k += BATCH;
+
+                if self.exhausted(context) {
+                    return None;
+                }
+            }
+
+            r *= 2;
+            if r > max_steps {
+                return None;
+            }
+        }
+
+        if g == context.modulus {
+            // The batch overshot, so redo its steps one gcd at a time.
+            loop {
+                saved_y = f(&saved_y);
+                g = context.gcd(&context.sub(&x, &saved_y));
+                if g != BigInt::from(1u8) {
+                    break;
+                }
+            }
+        }
+
+        if g == context.modulus {
+            None
+        } else {
+            Some(g)
+        }
+    }
+
+    fn ecm(&self, context: &Montgomery) -> Option<BigInt> {
+        // Stage 1 bounds and curve counts from the GMP-ECM recommendations
+        // for factors of 15, 20, 25, 30, 35 and 40 digits.
+        const SCHEDULE: [(u64, u32); 6] = [
+            (2_000, 25),
+            (11_000, 90),
+            (50_000, 300),
+            (250_000, 700),
+            (1_000_000, 1_800),
+            (3_000_000, 5_100),
+        ];
+
+        let mut sigma = 6u64;
+        let mut level = 0;
+        loop {
+            let (b1, curves) = SCHEDULE[level];
+            let b2 = b1 * 50;
+            let sieve = Sieve::new(b2 + ECM_STAGE2_STEP);
+
+            for _ in 0..curves {
+                if self.exhausted(context) {
+                    return None;
+                }
+                sigma += 1;
+                if let Some(d) = self.ecm_curve(context, sigma, b1, b2, &sieve) {
+                    return Some(d);
+                }
+            }
+
+            // Keep trying the biggest bounds for as long as the budget allows.
+            level = (level + 1).min(SCHEDULE.len() - 1);
+        }
+    }
+
+    fn ecm_curve(
+        &self,
+        context: &Montgomery,
+        sigma: u64,
+        b1: u64,
+        b2: u64,
+        sieve: &Sieve,
+    ) -> Option<BigInt> {
+        let n = &context.modulus;
+        let one = BigInt::from(1u8);
+
+        // Suyama's parametrization of a Montgomery curve with a known point:
+        // u = sigma^2 - 5, v = 4 sigma, start at (u^3 : v^3) and
+        // (A + 2) / 4 = (v - u)^3 (3u + v) / (16 u^3 v).
+        let sigma = BigInt::from(sigma);
+        let u = (&sigma * &sigma - 5u8) % n;
+        let v = (sigma * 4u8) % n;
+        let u3 = u.pow(3) % n;
+        let v3 = v.pow(3) % n;
+        let numerator = ((&v - &u).pow(3) * (u * 3u8 + &v)).mod_floor(n);
+        let denominator = (u3.abs_value() * v * 16u8) % n;
+        let a24 = match denominator.mod_inverse(n) {
+            Some(inverse) => (numerator * inverse) % n,
+            None => {
+                let g = denominator.gcd(n);
+                return if g != one && g != *n { Some(g) } else { None };
+            }
+        };
+
+        let curve = Curve {
+            context,
+            a24: context.to_montgomery(&a24),
+        };
+        let mut q = Point {
+            x: context.to_montgomery(&u3),
+            z: context.to_montgomery(&v3),
+        };
+
+        // Stage 1: multiply by every prime power up to B1.
+        for p in sieve.primes(2, b1) {
+            let mut power = p;
+            while power * p <= b1 {
+                power *= p;
+            }
+            q = curve.ladder(&q, power);
+            if self.exhausted(context) {
+                return None;
+            }
+        }
+
+        let g = context.gcd(&q.z);
+        if g == *n {
+            return None;
+        }
+        if g != one {
+            return Some(g);
+        }
+
+        // Stage 2: catch one more prime between B1 and B2. A prime
+        // p = k D +- j kills the point exactly when (k D) Q and j Q have the
+        // same x coordinate, so it is enough to accumulate the differences
+        // of the cross products.
+        const D: u64 = ECM_STAGE2_STEP;
+        let q2 = curve.double(&q);
+        let mut baby = vec![q.clone(), curve.add(&q2, &q, &q)];
+        for j in 2..(D / 4) as usize {
+            let next = curve.add(&baby[j - 1], &q2, &baby[j - 2]);
+            baby.push(next);
+        }
+        // baby[i] is (2i + 1) Q, only the ones coprime to D matter.
+        let baby: Vec<(u64, Point)> = baby
+            .into_iter()
+            .enumerate()
+            .map(|(i, point)| (2 * i as u64 + 1, point))
+            .filter(|(j, _)| j % 3 != 0 && j % 5 != 0 && j % 7 != 0)
+            .collect();
+
+        let step = curve.ladder(&q, D);
+        let first = (b1 / D).max(1);
+        let mut previous = curve.ladder(&q, (first - 1) * D);
+        let mut giant = curve.ladder(&q, first * D);
+        let mut acc = context.one.clone();
+
+        for k in first..=b2 / D {
+            for (j, point) in baby.iter() {
+                let (above, below) = (k * D + j, k * D - j);
+                if (above > b1 && sieve.is_prime(above)) || (below > b1 && sieve.is_prime(below)) {
+                    let cross = context.sub(
+                        &context.mul(&giant.x, &point.z),
+                        &context.mul(&point.x, &giant.z),
+                    );
+                    acc = context.mul(&acc, &cross);
+                }
+            }
+            if self.exhausted(context) {
+                return None;
+            }
+
+            let next = curve.add(&giant, &step, &previous);
+            previous = std::mem::replace(&mut giant, next);
+        }
+
+        let g = context.gcd(&acc);
+        if g != one && g != *n {
+            Some(g)
+        } else {
+            None
+        }
+    }
+}
+
+// Checks whether n = r^k for some prime k, which neither rho nor ECM handle
+// well.
+fn perfect_power(n: &BigInt) -> Option<(BigInt, u32)> {
+    // All the prime factors left are bigger than the sieve limit of the trial
+    // division, which bounds the exponent.
+    let max_exp = (n.bit_len() / 10) as u32;
+    for &k in small_primes().iter().take_while(|&&p| p <= max_exp) {
+        let root = n.nth_root(k);
+        if root.pow(k) == *n {
+            return Some((root, k));
+        }
+    }
+    None
+}
+
+const ECM_STAGE2_STEP: u64 = 210;
+
+struct Sieve {
+    composite: Vec<u64>,
+}
+
+impl Sieve {
+    fn new(limit: u64) -> Self {
+        let mut composite = vec![0u64; (limit / 64 + 1) as usize];
+        composite[0] |= 0b11;
+        let mut i = 2;
+        while i * i <= limit {
+            if composite[(i / 64) as usize] >> (i % 64) & 1 == 0 {
+                for j in (i * i..=limit).step_by(i as usize) {
+                    composite[(j / 64) as usize] |= 1 << (j % 64);
+                }
+            }
+            i += 1;
+        }
+        Sieve { composite }
+    }
+
+    fn is_prime(&self, n: u64) -> bool {
+        self.composite[(n / 64) as usize] >> (n % 64) & 1 == 0
+    }
+
+    fn primes(&self, from: u64, to: u64) -> impl Iterator<Item = u64> + '_ {
+        (from..=to).filter(move |&n| self.is_prime(n))
+    }
+}
+
+// Arithmetic modulo an odd n on numbers kept as x R mod n with R = 2^(32 k),
+// where k is the number of limbs of n. All values have exactly k limbs.
+struct Montgomery {
+    modulus: BigInt,
+    n: Vec<u32>,
+    // -n^-1 mod 2^32
+    n_inv: u32,
+    r2: Vec<u32>,
+    one: Vec<u32>,
+    multiplications: Cell<u64>,
+}
+
+impl Montgomery {
+    fn new(modulus: &BigInt) -> Self {
+        let n = modulus.limbs.clone();
+        let k = n.len();
+
+        // Newton's iteration doubles the correct low bits every time.
+        let mut inverse = 1u32;
+        for _ in 0..5 {
+            inverse = inverse.wrapping_mul(2u32.wrapping_sub(n[0].wrapping_mul(inverse)));
+        }
+
+        let r = BigInt::from(1u8) << (LIMB_BITS as usize * k);
+        let one = (&r % modulus).limbs;
+        let r2 = ((&r * &r) % modulus).limbs;
+
+        let pad = |mut x: Vec<u32>| {
+            x.resize(k, 0);
+            x
+        };
+        Montgomery {
+            modulus: modulus.abs_value(),
+            n_inv: inverse.wrapping_neg(),
+            r2: pad(r2),
+            one: pad(one),
+            n,
+            multiplications: Cell::new(0),
+        }
+    }
+
+    fn to_montgomery(&self, x: &BigInt) -> Vec<u32> {
+        let mut x = x.mod_floor(&self.modulus).limbs;
+        x.resize(self.n.len(), 0);
+        self.mul(&x, &self.r2)
+    }
+
+    // a b R^-1 mod n, by coarsely integrated operand scanning.
+    fn mul(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        self.multiplications.set(self.multiplications.get() + 1);
+
+        let k = self.n.len();
+        let mut t = vec![0u32; k + 2];
+        for &b_i in b {
+            let mut carry = 0u64;
+            for j in 0..k {
+                let sum = t[j] as u64 + a[j] as u64 * b_i as u64 + carry;
+                t[j] = sum as u32;
+                carry = sum >> LIMB_BITS;
+            }
+            let sum = t[k] as u64 + carry;
+            t[k] = sum as u32;
+            t[k + 1] = (sum >> LIMB_BITS) as u32;
+
+            let m = t[0].wrapping_mul(self.n_inv);
+            let mut carry = (t[0] as u64 + m as u64 * self.n[0] as u64) >> LIMB_BITS;
+            for j in 1..k {
+                let sum = t[j] as u64 + m as u64 * self.n[j] as u64 + carry;
+                t[j - 1] = sum as u32;
+                carry = sum >> LIMB_BITS;
+            }
+            let sum = t[k] as u64 + carry;
+            t[k - 1] = sum as u32;
+            t[k] = t[k + 1] + (sum >> LIMB_BITS) as u32;
+        }
+
+        t.truncate(k + 1);
+        self.reduce(t)
+    }
+
+    // Brings a value below 2n back into [0, n) with exactly k limbs.
+    fn reduce(&self, mut x: Vec<u32>) -> Vec<u32> {
+        let k = self.n.len();
+        trim_zero_limbs(&mut x);
+        if cmp_limbs(&x, &self.n) != Ordering::Less {
+            sub_assign_limbs(&mut x, &self.n);
+        }
+        x.resize(k, 0);
+        x
+    }
+
+    fn add(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let mut sum = a.to_vec();
+        crate::add_assign_limbs(&mut sum, b);
+        self.reduce(sum)
+    }
+
+    fn sub(&self, a: &[u32], b: &[u32]) -> Vec<u32> {
+        let (a, b) = (crate::trimmed(a), crate::trimmed(b));
+        let mut result = if cmp_limbs(a, b) == Ordering::Less {
+            let mut result = a.to_vec();
+            crate::add_assign_limbs(&mut result, &self.n);
+            result
+        } else {
+            a.to_vec()
+        };
+        sub_assign_limbs(&mut result, b);
+        result.resize(self.n.len(), 0);
+        result
+    }
+
+    // gcd(x, n) does not care about the factor R, since R is coprime to n.
+    fn gcd(&self, x: &[u32]) -> BigInt {
+        BigInt::from_limbs(1, x.to_vec()).gcd(&self.modulus)
+    }
+}
+
+// A point on a Montgomery curve in projective (X : Z) coordinates.
+#[derive(Clone)]
+struct Point {
+    x: Vec<u32>,
+    z: Vec<u32>,
+}
+
+struct Curve<'a> {
+    context: &'a Montgomery,
+    a24: Vec<u32>,
+}
+
+impl Curve<'_> {
+    fn double(&self, p: &Point) -> Point {
+        let c = self.context;
+        let sum = c.add(&p.x, &p.z);
+        let difference = c.sub(&p.x, &p.z);
+        let sum2 = c.mul(&sum, &sum);
+        let difference2 = c.mul(&difference, &difference);
+        let t = c.sub(&sum2, &difference2);
+        Point {
+            x: c.mul(&sum2, &difference2),
+            z: c.mul(&t, &c.add(&difference2, &c.mul(&self.a24, &t))),
+        }
+    }
+
+    // p + q, given p - q.
+    fn add(&self, p: &Point, q: &Point, difference: &Point) -> Point {
+        let c = self.context;
+        let u = c.mul(&c.sub(&p.x, &p.z), &c.add(&q.x, &q.z));
+        let v = c.mul(&c.add(&p.x, &p.z), &c.sub(&q.x, &q.z));
+        let sum = c.add(&u, &v);
+        let diff = c.sub(&u, &v);
+        Point {
+            x: c.mul(&difference.z, &c.mul(&sum, &sum)),
+            z: c.mul(&difference.x, &c.mul(&diff, &diff)),
+        }
+    }
+
+    // k p with the Montgomery ladder.
+    fn ladder(&self, p: &Point, k: u64) -> Point {
+        if k == 0 {
+            return Point {
+                x: self.context.one.clone(),
+                z: vec![0; self.context.n.len()],
+            };
+        }
+
+        let mut r0 = p.clone();
+        let mut r1 = self.double(p);
+        for bit in (0..63 - k.leading_zeros()).rev() {
+            if (k >> bit) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r1, &r0, p);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{factor, factor_with_budget, FactorBudget, Factorizer, Montgomery};
+    use crate::BigInt;
+    use std::str::FromStr;
+    use std::time::Instant;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    fn factors(pairs: &[(&str, u32)]) -> Vec<(BigInt, u32)> {
+        pairs.iter().map(|&(p, k)| (bigint(p), k)).collect()
+    }
+
+    fn product(pairs: &[(BigInt, u32)]) -> BigInt {
+        pairs
+            .iter()
+            .fold(BigInt::from(1u8), |acc, (p, k)| acc * p.pow(*k))
+    }
+
+    #[test]
+    fn test_small_numbers() {
+        assert_eq!(factor(&bigint("0")), factors(&[("0", 1)]));
+        assert_eq!(factor(&bigint("1")), factors(&[]));
+        assert_eq!(factor(&bigint("-1")), factors(&[("-1", 1)]));
+        assert_eq!(
+            factor(&bigint("-360")),
+            factors(&[("-1", 1), ("2", 3), ("3", 2), ("5", 1)])
+        );
+
+        for n in 2u32..3000 {
+            let result = factor(&BigInt::from(n));
+            assert_eq!(product(&result), BigInt::from(n));
+            for (p, _) in result.iter() {
+                assert!(p.is_probable_prime_bpsw());
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_factorizations() {
+        assert_eq!(
+            factor(&bigint("600851475143")),
+            factors(&[("71", 1), ("839", 1), ("1471", 1), ("6857", 1)])
+        );
+        // F6 = 2^64 + 1
+        assert_eq!(
+            factor(&((BigInt::from(1u8) << 64) + 1u8)),
+            factors(&[("274177", 1), ("67280421310721", 1)])
+        );
+        // A prime power and a square of a semiprime.
+        assert_eq!(
+            factor(&bigint("1000000007").pow(5)),
+            factors(&[("1000000007", 5)])
+        );
+        assert_eq!(
+            factor(&(bigint("1000003") * bigint("1000033")).pow(2)),
+            factors(&[("1000003", 2), ("1000033", 2)])
+        );
+        // 2^67 - 1, Cole's factorization.
+        assert_eq!(
+            factor(&((BigInt::from(1u8) << 67) - 1u8)),
+            factors(&[("193707721", 1), ("761838257287", 1)])
+        );
+    }
+
+    #[test]
+    fn test_ecm() {
+        // A 13 digit factor is out of reach of a short rho run, so this
+        // exercises the curves.
+        let n =
+            bigint("1000000000039") * bigint("100000000000000000000000000000000000000000000000151");
+        let context = Montgomery::new(&n);
+        let factorizer = Factorizer {
+            budget: FactorBudget::unlimited(),
+            start: Instant::now(),
+            spent: 0,
+        };
+        let d = factorizer.ecm(&context).unwrap();
+        assert!(d == bigint("1000000000039") || &n / &d == bigint("1000000000039"));
+    }
+
+    #[test]
+    fn test_sixty_digits() {
+        // Two factors for rho, one for ECM and a prime cofactor.
+        let primes = [
+            "1000000007",
+            "998244353",
+            "1000000000039",
+            "100000000000000000000000000319",
+        ];
+        let n = primes
+            .iter()
+            .fold(BigInt::from(1u8), |acc, p| acc * bigint(p));
+        let mut expected = factors(&primes.iter().map(|&p| (p, 1)).collect::<Vec<_>>());
+        expected.sort();
+        assert_eq!(factor(&n), expected);
+    }
+
+    #[test]
+    fn test_budget() {
+        // Two 25 digit primes, far too much for a budget of a few thousand
+        // multiplications.
+        let hard = bigint("1000000000000000000000007") * bigint("1000000000000000000000049");
+        let n = &hard * bigint("12");
+        let result = factor_with_budget(&n, FactorBudget::unlimited().with_iterations(5_000));
+
+        assert!(!result.is_complete());
+        assert_eq!(result.factors(), &factors(&[("2", 2), ("3", 1)])[..]);
+        assert_eq!(result.unfactored(), &[(hard, 1)][..]);
+
+        let result = factor_with_budget(
+            &bigint("1000000000000000000000007").pow(2),
+            FactorBudget::unlimited().with_iterations(1),
+        );
+        assert!(result.is_complete());
+
+        let start = Instant::now();
+        let result = factor_with_budget(
+            &(bigint("1000000000000000000000007") * bigint("1000000000000000000000049")),
+            FactorBudget::unlimited().with_duration(std::time::Duration::from_millis(200)),
+        );
+        assert!(start.elapsed().as_secs() < 5);
+        assert_eq!(
+            product(result.factors()) * product(result.unfactored()),
+            bigint("1000000000000000000000007") * bigint("1000000000000000000000049")
+        );
+    }
+
+    #[test]
+    fn test_montgomery() {
+        let n = bigint("340282366920938463463374607431768211507");
+        let context = Montgomery::new(&n);
+        let (a, b) = (
+            bigint("123456789123456789123456789"),
+            bigint("-987654321987654321"),
+        );
+        let product = context.mul(&context.to_montgomery(&a), &context.to_montgomery(&b));
+
+        // Multiplying by a plain 1 divides out R again.
+        let mut one = vec![0; product.len()];
+        one[0] = 1;
+        let product = BigInt::from_limbs(1, context.mul(&product, &one));
+        assert_eq!(product, (&a * &b).mod_floor(&n));
+    }
+}
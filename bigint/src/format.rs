@@ -0,0 +1,324 @@
+// Configurable output: digit grouping for plain numbers, and scientific or
+// engineering notation with a fixed number of significant digits. A
+// `NumberFormat` is built once and applied with `BigInt::formatted`; the
+// result still honours the sign, width, fill and alignment flags.
+
+use crate::{radix, BigDecimal, BigInt, RoundingMode};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Notation {
+    Plain,
+    // d.ddd e n
+    Scientific,
+    // Like scientific, but the exponent is a multiple of three.
+    Engineering,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    separator: Option<char>,
+    // Size of the lowest group and of every group above it.
+    first_group: usize,
+    group: usize,
+    notation: Notation,
+    significant_digits: u64,
+    rounding: RoundingMode,
+}
+
+impl Default for NumberFormat {
+    fn default() -> Self {
+        NumberFormat {
+            separator: None,
+            first_group: 3,
+            group: 3,
+            notation: Notation::Plain,
+            significant_digits: 6,
+            rounding: RoundingMode::HalfEven,
+        }
+    }
+}
+
+impl NumberFormat {
+    // Plain digits, no grouping.
+    pub fn new() -> Self {
+        NumberFormat::default()
+    }
+
+    // Groups of three, like 1,234,567.
+    pub fn grouped(separator: char) -> Self {
+        NumberFormat::new().with_separator(separator)
+    }
+
+    // Lakh and crore grouping, like 12,34,567.
+    pub fn indian(separator: char) -> Self {
+        NumberFormat::new()
+            .with_separator(separator)
+            .with_groups(3, 2)
+    }
+
+    pub fn with_separator(mut self, separator: char) -> Self {
+        self.separator = Some(separator);
+        self
+    }
+
+    // The lowest group has `first` digits and the ones above it `rest`.
+    // Both have to be positive.
+    pub fn with_groups(mut self, first: usize, rest: usize) -> Self {
+        assert!(first > 0 && rest > 0, "digit groups can't be empty");
+        self.first_group = first;
+        self.group = rest;
+        self
+    }
+
+    pub fn scientific(mut self, significant_digits: u64) -> Self {
+        self.notation = Notation::Scientific;
+        self.significant_digits = significant_digits;
+        self
+    }
+
+    pub fn engineering(mut self, significant_digits: u64) -> Self {
+        self.notation = Notation::Engineering;
+        self.significant_digits = significant_digits;
+        self
+    }
+
+    // How scientific and engineering notation drop digits, half to even by
+    // default.
+    pub fn with_rounding(mut self, rounding: RoundingMode) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub(crate) fn group(&self, digits: &str) -> String {
+        let separator = match self.separator {
+            Some(separator) => separator,
+            None => return digits.to_string(),
+        };
+
+        let mut groups = Vec::new();
+        let mut end = digits.len();
+        let mut size = self.first_group;
+        while end > size {
+            groups.push(&digits[end - size..end]);
+            end -= size;
+            size = self.group;
+        }
+        groups.push(&digits[..end]);
+
+        let mut result = String::with_capacity(digits.len() + groups.len());
+        for (i, group) in groups.iter().rev().enumerate() {
+            if i > 0 {
+                result.push(separator);
+            }
+            result.push_str(group);
+        }
+        result
+    }
+
+    // The magnitude as mantissa and exponent.
+    fn exponential(&self, n: &BigInt) -> String {
+        let digits = self.significant_digits.max(1) as usize;
+        let rounded = BigDecimal::from(n.abs_value()).with_precision(digits as u64, self.rounding);
+
+        // Pad the mantissa to show exactly the requested digits.
+        let mut mantissa = radix::magnitude_to_radix(&rounded.unscaled().limbs, 10);
+        let exponent = if n.is_zero() {
+            0
+        } else {
+            mantissa.len() as i64 - 1 - rounded.scale()
+        };
+        mantissa.extend(std::iter::repeat_n(
+            '0',
+            digits.saturating_sub(mantissa.len()),
+        ));
+
+        let (whole, exponent) = match self.notation {
+            Notation::Engineering => {
+                let shift = exponent.rem_euclid(3) as usize;
+                // Not enough significant digits to fill the integer part.
+                mantissa.extend(std::iter::repeat_n(
+                    '0',
+                    (shift + 1).saturating_sub(mantissa.len()),
+                ));
+                (shift + 1, exponent - shift as i64)
+            }
+            _ => (1, exponent),
+        };
+
+        if whole < mantissa.len() {
+            format!("{}.{}e{}", &mantissa[..whole], &mantissa[whole..], exponent)
+        } else {
+            format!("{}e{}", mantissa, exponent)
+        }
+    }
+}
+
+pub struct Formatted<'a> {
+    bigint: &'a BigInt,
+    format: &'a NumberFormat,
+}
+
+impl BigInt {
+    pub fn formatted<'a>(&'a self, format: &'a NumberFormat) -> Formatted<'a> {
+        Formatted {
+            bigint: self,
+            format,
+        }
+    }
+}
+
+impl fmt::Display for Formatted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let body = match self.format.notation {
+            Notation::Plain => self
+                .format
+                .group(&radix::magnitude_to_radix(&self.bigint.limbs, 10)),
+            _ => self.format.exponential(self.bigint),
+        };
+        f.pad_integral(!self.bigint.is_negative(), "", &body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NumberFormat;
+    use crate::{BigInt, RoundingMode};
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_display_flags() {
+        let n = bigint("12345");
+        assert_eq!(format!("{:+}", n), "+12345");
+        assert_eq!(format!("{:+}", -&n), "-12345");
+        assert_eq!(format!("{:+}", BigInt::zero()), "+0");
+        assert_eq!(format!("{:>8}", n), "   12345");
+        assert_eq!(format!("{:<8}|", -&n), "-12345  |");
+        assert_eq!(format!("{:*^9}", n), "**12345**");
+        assert_eq!(format!("{:08}", -&n), "-0012345");
+        assert_eq!(format!("{:+08}", n), "+0012345");
+        assert_eq!(format!("{:3}", n), "12345");
+
+        // Same as the primitive integers.
+        for &x in [0i64, 7, -7, 123456789, -987654321].iter() {
+            let b = BigInt::from(x);
+            assert_eq!(format!("{:+012}", b), format!("{:+012}", x));
+            assert_eq!(format!("{:_<10}", b), format!("{:_<10}", x));
+            assert_eq!(format!("{:>+10}", b), format!("{:>+10}", x));
+        }
+    }
+
+    #[test]
+    fn test_grouping() {
+        let n = bigint("1234567890");
+        assert_eq!(n.formatted(&NumberFormat::new()).to_string(), "1234567890");
+        assert_eq!(
+            n.formatted(&NumberFormat::grouped(',')).to_string(),
+            "1,234,567,890"
+        );
+        assert_eq!(
+            (-&n).formatted(&NumberFormat::grouped('.')).to_string(),
+            "-1.234.567.890"
+        );
+        assert_eq!(
+            n.formatted(&NumberFormat::indian(',')).to_string(),
+            "1,23,45,67,890"
+        );
+        assert_eq!(
+            n.formatted(&NumberFormat::grouped('\u{2009}').with_groups(4, 4))
+                .to_string(),
+            "12\u{2009}3456\u{2009}7890"
+        );
+
+        let indian = NumberFormat::indian(',');
+        for &(s, expected) in [
+            ("0", "0"),
+            ("999", "999"),
+            ("1000", "1,000"),
+            ("99999", "99,999"),
+            ("100000", "1,00,000"),
+            ("-10000000", "-1,00,00,000"),
+        ]
+        .iter()
+        {
+            assert_eq!(bigint(s).formatted(&indian).to_string(), expected);
+        }
+
+        let format = NumberFormat::grouped(',');
+        assert_eq!(
+            format!("{:>12}", bigint("1234567").formatted(&format)),
+            "   1,234,567"
+        );
+        assert_eq!(format!("{:+}", bigint("1234").formatted(&format)), "+1,234");
+    }
+
+    #[test]
+    fn test_scientific() {
+        let format = NumberFormat::new().scientific(3);
+        let cases = [
+            ("0", "0.00e0"),
+            ("7", "7.00e0"),
+            ("12345", "1.23e4"),
+            ("-12355", "-1.24e4"),
+            ("99960", "1.00e5"),
+            ("1000000", "1.00e6"),
+        ];
+        for &(s, expected) in cases.iter() {
+            assert_eq!(bigint(s).formatted(&format).to_string(), expected, "{}", s);
+        }
+
+        // 2^100 = 1267650600228229401496703205376
+        let n = BigInt::one() << 100;
+        assert_eq!(
+            n.formatted(&NumberFormat::new().scientific(10)).to_string(),
+            "1.267650600e30"
+        );
+        assert_eq!(
+            n.formatted(&NumberFormat::new().scientific(1)).to_string(),
+            "1e30"
+        );
+        assert_eq!(
+            bigint("12500")
+                .formatted(&NumberFormat::new().scientific(2))
+                .to_string(),
+            "1.2e4"
+        );
+        assert_eq!(
+            bigint("12500")
+                .formatted(
+                    &NumberFormat::new()
+                        .scientific(2)
+                        .with_rounding(RoundingMode::HalfUp)
+                )
+                .to_string(),
+            "1.3e4"
+        );
+        assert_eq!(
+            format!("{:>10}", bigint("-12345").formatted(&format)),
+            "   -1.23e4"
+        );
+    }
+
+    #[test]
+    fn test_engineering() {
+        let cases = [
+            ("0", 3, "0.00e0"),
+            ("7", 3, "7.00e0"),
+            ("12345", 3, "12.3e3"),
+            ("123456", 3, "123e3"),
+            ("1234567", 4, "1.235e6"),
+            ("-98765432", 2, "-99e6"),
+            ("12345", 1, "10e3"),
+            ("123456", 1, "100e3"),
+            ("999999", 2, "1.0e6"),
+        ];
+        for &(s, digits, expected) in cases.iter() {
+            let format = NumberFormat::new().engineering(digits);
+            assert_eq!(bigint(s).formatted(&format).to_string(), expected, "{}", s);
+        }
+    }
+}
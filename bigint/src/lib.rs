@@ -0,0 +1,1799 @@
+mod biguint;
+mod bits;
+mod convert;
+mod decimal;
+mod encoding;
+mod factor;
+mod format;
+mod locale;
+mod number_theory;
+mod power;
+mod prime;
+mod radix;
+mod ratio;
+#[cfg(feature = "serde")]
+mod serialization;
+mod stream;
+mod words;
+
+pub use biguint::{BigUint, Delimited};
+pub use convert::TryFromBigIntError;
+pub use decimal::{BigDecimal, RoundingMode};
+pub use encoding::DecodeVarintError;
+pub use factor::{factor, factor_with_budget, FactorBudget, Factorization};
+pub use format::{Formatted, NumberFormat};
+pub use locale::{DigitShape, Grouping, NumberLocale};
+pub use ratio::Ratio;
+#[cfg(feature = "serde")]
+pub use serialization::number as serde_number;
+pub use stream::ReadError;
+pub use words::{Spelled, Spelling};
+
+// The magnitude is stored as base 2^32 limbs, least significant first, with
+// no trailing zero limbs. Zero has no limbs and is always positive, which
+// keeps the derived equality and hash consistent.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BigInt {
+    sign: i8,
+    limbs: Vec<u32>,
+}
+
+const LIMB_BITS: u32 = 32;
+
+impl BigInt {
+    pub fn new() -> Self {
+        BigInt {
+            sign: 1,
+            limbs: Vec::new(),
+        }
+    }
+
+    pub fn zero() -> Self {
+        BigInt::new()
+    }
+
+    pub fn one() -> Self {
+        BigInt {
+            sign: 1,
+            limbs: vec![1],
+        }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.is_empty()
+    }
+
+    pub fn is_one(&self) -> bool {
+        self.sign == 1 && self.limbs == [1]
+    }
+
+    pub fn div_rem(&self, other: &BigInt) -> Result<(BigInt, BigInt), DivisionByZero> {
+        if other.limbs.is_empty() {
+            return Err(DivisionByZero);
+        }
+
+        let (quotient, remainder) = div_rem_limbs(&self.limbs, &other.limbs);
+        Ok((
+            BigInt::from_limbs(self.sign * other.sign, quotient),
+            BigInt::from_limbs(self.sign, remainder),
+        ))
+    }
+
+    // Builds a number from a sign and its decimal digits, most significant
+    // first. Leading zeros are dropped and zero always comes out positive.
+    // The sign must be 1 or -1, or 0 when all the digits are zero.
+    pub fn from_parts(sign: i8, digits: &[u8]) -> Result<Self, FromPartsError> {
+        if let Some(i) = digits.iter().position(|&d| d > 9) {
+            return Err(FromPartsError::InvalidDigit(i));
+        }
+
+        let values: Vec<u32> = digits.iter().map(|&d| d as u32).collect();
+        let limbs = radix::pack_digits(&values, 10);
+        match sign {
+            1 | -1 => Ok(BigInt::from_limbs(sign, limbs)),
+            0 if limbs.is_empty() => Ok(BigInt::new()),
+            _ => Err(FromPartsError::InvalidSign(sign)),
+        }
+    }
+
+    fn from_limbs(sign: i8, mut limbs: Vec<u32>) -> Self {
+        trim_zero_limbs(&mut limbs);
+        if limbs.is_empty() {
+            return BigInt::new();
+        }
+        let result = BigInt { sign, limbs };
+        result.debug_check();
+        result
+    }
+
+    // Every value has a sign of 1 or -1, no trailing zero limbs and zero is
+    // positive. Debug builds check this wherever a value is built or changed
+    // in place.
+    #[inline]
+    fn debug_check(&self) {
+        debug_assert!(
+            self.sign == 1 || self.sign == -1,
+            "invalid sign {}",
+            self.sign
+        );
+        debug_assert!(self.limbs.last() != Some(&0), "trailing zero limb");
+        debug_assert!(self.sign == 1 || !self.limbs.is_empty(), "negative zero");
+    }
+
+    // 1, 0 or -1.
+    pub fn signum(&self) -> i8 {
+        if self.limbs.is_empty() {
+            0
+        } else {
+            self.sign
+        }
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.signum() == 1
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.signum() == -1
+    }
+}
+
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    // There are no digits, either because the input is empty or because it
+    // ends right after the sign or a radix prefix.
+    Empty,
+    LoneSign,
+    InvalidDigit,
+    InvalidRadix,
+    ZeroDenominator,
+    OutOfRange,
+    MisplacedSeparator,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    kind: ParseErrorKind,
+    position: usize,
+    character: Option<char>,
+}
+
+impl ParseError {
+    fn new(kind: ParseErrorKind, position: usize, character: Option<char>) -> Self {
+        ParseError {
+            kind,
+            position,
+            character,
+        }
+    }
+
+    pub fn kind(&self) -> ParseErrorKind {
+        self.kind
+    }
+
+    // Byte offset of the problem in the parsed string.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn character(&self) -> Option<char> {
+        self.character
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.kind {
+            ParseErrorKind::Empty => write!(f, "no digits to parse at byte {}", self.position),
+            ParseErrorKind::LoneSign => write!(f, "sign without digits at byte {}", self.position),
+            ParseErrorKind::InvalidDigit => write!(
+                f,
+                "invalid digit {:?} at byte {}",
+                self.character.unwrap_or_default(),
+                self.position
+            ),
+            ParseErrorKind::InvalidRadix => write!(f, "radix must be in the range 2..=36"),
+            ParseErrorKind::ZeroDenominator => {
+                write!(f, "zero denominator at byte {}", self.position)
+            }
+            ParseErrorKind::OutOfRange => write!(f, "value out of range at byte {}", self.position),
+            ParseErrorKind::MisplacedSeparator => {
+                write!(f, "misplaced separator at byte {}", self.position)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug)]
+pub struct DivisionByZero;
+
+impl fmt::Display for DivisionByZero {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "division by zero")
+    }
+}
+
+impl std::error::Error for DivisionByZero {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FromPartsError {
+    InvalidSign(i8),
+    // Index of the first digit above 9.
+    InvalidDigit(usize),
+}
+
+impl fmt::Display for FromPartsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FromPartsError::InvalidSign(sign) => write!(f, "invalid sign {}", sign),
+            FromPartsError::InvalidDigit(i) => write!(f, "invalid decimal digit at index {}", i),
+        }
+    }
+}
+
+impl std::error::Error for FromPartsError {}
+
+impl FromStr for BigInt {
+    type Err = ParseError;
+
+    // The empty string is zero, as it always has been. A sign or a prefix
+    // with nothing after it is still an error.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(BigInt::new());
+        }
+        let (sign, digits) = radix::split_sign(s)?;
+
+        // A `0x`, `0o` or `0b` prefix after the sign selects the radix.
+        let (radix, digits) = radix::split_prefix(digits);
+
+        let limbs = radix::parse_magnitude(digits, radix, s.len() - digits.len())?;
+        Ok(BigInt::from_limbs(sign, limbs))
+    }
+}
+
+use std::fmt;
+
+// Honours `+`, `0`, width, fill and alignment like the primitive integers.
+impl fmt::Display for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let digits = radix::magnitude_to_radix(&self.limbs, 10);
+        f.pad_integral(!self.is_negative(), "", &digits)
+    }
+}
+
+use std::cmp::Ordering;
+
+impl PartialOrd for BigInt {
+    fn partial_cmp(&self, other: &BigInt) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BigInt {
+    fn cmp(&self, other: &BigInt) -> Ordering {
+        if self.sign != other.sign {
+            return match self.sign {
+                -1 => Ordering::Less,
+                1 => Ordering::Greater,
+                _ => unreachable!(),
+            };
+        }
+
+        let order = cmp_limbs(&self.limbs, &other.limbs);
+
+        if self.sign == 1 {
+            order
+        } else {
+            order.reverse()
+        }
+    }
+}
+
+use std::iter::{Product, Sum};
+use std::ops::{
+    Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Rem, RemAssign, Sub, SubAssign,
+};
+
+impl BigInt {
+    // self = self + sign * limbs, reusing the buffer of `self`.
+    fn add_signed(&mut self, sign: i8, limbs: &[u32]) {
+        if self.sign == sign {
+            add_assign_limbs(&mut self.limbs, limbs);
+            return;
+        }
+
+        match cmp_limbs(&self.limbs, limbs) {
+            Ordering::Greater => sub_assign_limbs(&mut self.limbs, limbs),
+            Ordering::Less => {
+                rsub_assign_limbs(&mut self.limbs, limbs);
+                self.sign = sign;
+            }
+            Ordering::Equal => {
+                self.limbs.clear();
+                self.sign = 1;
+            }
+        }
+        self.debug_check();
+    }
+}
+
+impl AddAssign<&BigInt> for BigInt {
+    fn add_assign(&mut self, other: &BigInt) {
+        self.add_signed(other.sign, &other.limbs);
+    }
+}
+
+impl SubAssign<&BigInt> for BigInt {
+    fn sub_assign(&mut self, other: &BigInt) {
+        self.add_signed(-other.sign, &other.limbs);
+    }
+}
+
+impl MulAssign<&BigInt> for BigInt {
+    fn mul_assign(&mut self, other: &BigInt) {
+        *self = &*self * other;
+    }
+}
+
+impl DivAssign<&BigInt> for BigInt {
+    fn div_assign(&mut self, other: &BigInt) {
+        *self = &*self / other;
+    }
+}
+
+impl RemAssign<&BigInt> for BigInt {
+    fn rem_assign(&mut self, other: &BigInt) {
+        *self = &*self % other;
+    }
+}
+
+impl Add for BigInt {
+    type Output = BigInt;
+
+    fn add(mut self, mut other: Self) -> Self {
+        // Keep the result in whichever buffer is bigger.
+        if self.limbs.capacity() < other.limbs.capacity() {
+            std::mem::swap(&mut self, &mut other);
+        }
+        self += &other;
+        self
+    }
+}
+
+impl Add<&BigInt> for BigInt {
+    type Output = BigInt;
+
+    fn add(mut self, other: &BigInt) -> BigInt {
+        self += other;
+        self
+    }
+}
+
+impl Add<BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, mut other: BigInt) -> BigInt {
+        other += self;
+        other
+    }
+}
+
+impl Add<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn add(self, other: &BigInt) -> BigInt {
+        let mut result = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        result.extend_from_slice(&self.limbs);
+        BigInt::from_limbs(self.sign, result) + other
+    }
+}
+
+impl Sub for BigInt {
+    type Output = BigInt;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= &other;
+        self
+    }
+}
+
+impl Sub<&BigInt> for BigInt {
+    type Output = BigInt;
+
+    fn sub(mut self, other: &BigInt) -> BigInt {
+        self -= other;
+        self
+    }
+}
+
+impl Sub<BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, mut other: BigInt) -> BigInt {
+        other -= self;
+        -other
+    }
+}
+
+impl Sub<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn sub(self, other: &BigInt) -> BigInt {
+        let mut result = Vec::with_capacity(self.limbs.len().max(other.limbs.len()) + 1);
+        result.extend_from_slice(&self.limbs);
+        BigInt::from_limbs(self.sign, result) - other
+    }
+}
+
+impl Mul<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn mul(self, other: &BigInt) -> BigInt {
+        BigInt::from_limbs(self.sign * other.sign, mul_limbs(&self.limbs, &other.limbs))
+    }
+}
+
+impl Div<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn div(self, other: &BigInt) -> BigInt {
+        match self.div_rem(other) {
+            Ok((quotient, _)) => quotient,
+            Err(DivisionByZero) => panic!("attempt to divide by zero"),
+        }
+    }
+}
+
+impl Rem<&BigInt> for &BigInt {
+    type Output = BigInt;
+
+    fn rem(self, other: &BigInt) -> BigInt {
+        match self.div_rem(other) {
+            Ok((_, remainder)) => remainder,
+            Err(DivisionByZero) => {
+                panic!("attempt to calculate the remainder with a divisor of zero")
+            }
+        }
+    }
+}
+
+// Multiplication and division can't reuse their operands' buffers, so the
+// owned variants just forward to the borrowing ones.
+macro_rules! forward_binop_to_ref {
+    ($imp:ident, $method:ident) => {
+        impl $imp for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&BigInt> for BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: &BigInt) -> BigInt {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<BigInt> for &BigInt {
+            type Output = BigInt;
+
+            fn $method(self, other: BigInt) -> BigInt {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_binop_to_ref!(Mul, mul);
+forward_binop_to_ref!(Div, div);
+forward_binop_to_ref!(Rem, rem);
+
+macro_rules! forward_assign_to_ref {
+    ($imp:ident, $method:ident) => {
+        impl $imp for BigInt {
+            fn $method(&mut self, other: BigInt) {
+                self.$method(&other);
+            }
+        }
+    };
+}
+
+forward_assign_to_ref!(AddAssign, add_assign);
+forward_assign_to_ref!(SubAssign, sub_assign);
+forward_assign_to_ref!(MulAssign, mul_assign);
+forward_assign_to_ref!(DivAssign, div_assign);
+forward_assign_to_ref!(RemAssign, rem_assign);
+
+impl Neg for BigInt {
+    type Output = BigInt;
+
+    fn neg(mut self) -> BigInt {
+        if !self.limbs.is_empty() {
+            self.sign = -self.sign;
+        }
+        self.debug_check();
+        self
+    }
+}
+
+impl Neg for &BigInt {
+    type Output = BigInt;
+
+    fn neg(self) -> BigInt {
+        -self.clone()
+    }
+}
+
+impl Default for BigInt {
+    fn default() -> Self {
+        BigInt::new()
+    }
+}
+
+impl Sum for BigInt {
+    fn sum<I: Iterator<Item = BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::zero(), |mut acc, x| {
+            acc += &x;
+            acc
+        })
+    }
+}
+
+impl<'a> Sum<&'a BigInt> for BigInt {
+    fn sum<I: Iterator<Item = &'a BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::zero(), |mut acc, x| {
+            acc += x;
+            acc
+        })
+    }
+}
+
+impl Product for BigInt {
+    fn product<I: Iterator<Item = BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::one(), |acc, x| acc * x)
+    }
+}
+
+impl<'a> Product<&'a BigInt> for BigInt {
+    fn product<I: Iterator<Item = &'a BigInt>>(iter: I) -> BigInt {
+        iter.fold(BigInt::one(), |acc, x| acc * x)
+    }
+}
+
+// Helpers working on magnitudes, i.e. little-endian limb slices.
+
+fn trim_zero_limbs(limbs: &mut Vec<u32>) {
+    while limbs.last() == Some(&0) {
+        limbs.pop();
+    }
+}
+
+fn cmp_limbs(a: &[u32], b: &[u32]) -> Ordering {
+    if a.len() != b.len() {
+        return a.len().cmp(&b.len());
+    }
+
+    // The slice equality check compiles down to a memcmp, which is much faster
+    // than walking the limbs from the top when the magnitudes are equal.
+    if a == b {
+        return Ordering::Equal;
+    }
+
+    a.iter().rev().cmp(b.iter().rev())
+}
+
+fn add_assign_limbs(a: &mut Vec<u32>, b: &[u32]) {
+    add_shifted_limbs(a, b, 0);
+}
+
+// a = a + b * 2^(32 * offset)
+fn add_shifted_limbs(a: &mut Vec<u32>, b: &[u32], offset: usize) {
+    if a.len() < offset + b.len() {
+        a.resize(offset + b.len(), 0);
+    }
+
+    let mut carry = 0u64;
+    for (x, &y) in a[offset..].iter_mut().zip(b) {
+        let sum = *x as u64 + y as u64 + carry;
+        *x = sum as u32;
+        carry = sum >> LIMB_BITS;
+    }
+
+    for x in a[offset + b.len()..].iter_mut() {
+        if carry == 0 {
+            break;
+        }
+        let sum = *x as u64 + carry;
+        *x = sum as u32;
+        carry = sum >> LIMB_BITS;
+    }
+
+    if carry != 0 {
+        a.push(carry as u32);
+    }
+}
+
+// Expects `a >= b`.
+fn sub_assign_limbs(a: &mut Vec<u32>, b: &[u32]) {
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (diff, overflow1) = x.overflowing_sub(y);
+        let (diff, overflow2) = diff.overflowing_sub(borrow as u32);
+        *x = diff;
+        borrow = overflow1 || overflow2;
+    }
+
+    for x in a[b.len()..].iter_mut() {
+        if !borrow {
+            break;
+        }
+        let (diff, overflow) = x.overflowing_sub(1);
+        *x = diff;
+        borrow = overflow;
+    }
+
+    debug_assert!(!borrow);
+    trim_zero_limbs(a);
+}
+
+// a = b - a, expects `b >= a`.
+fn rsub_assign_limbs(a: &mut Vec<u32>, b: &[u32]) {
+    a.resize(b.len(), 0);
+
+    let mut borrow = false;
+    for (x, &y) in a.iter_mut().zip(b) {
+        let (diff, overflow1) = y.overflowing_sub(*x);
+        let (diff, overflow2) = diff.overflowing_sub(borrow as u32);
+        *x = diff;
+        borrow = overflow1 || overflow2;
+    }
+
+    debug_assert!(!borrow);
+    trim_zero_limbs(a);
+}
+
+// a = a * m + c
+fn mul_add_small(a: &mut Vec<u32>, m: u32, c: u32) {
+    let mut carry = c as u64;
+    for x in a.iter_mut() {
+        let product = *x as u64 * m as u64 + carry;
+        *x = product as u32;
+        carry = product >> LIMB_BITS;
+    }
+    if carry != 0 {
+        a.push(carry as u32);
+    }
+}
+
+fn div_rem_small(a: &[u32], d: u32) -> (Vec<u32>, u32) {
+    let mut quotient = vec![0; a.len()];
+    let mut remainder = 0u64;
+    for (q, &x) in quotient.iter_mut().zip(a).rev() {
+        let current = (remainder << LIMB_BITS) | x as u64;
+        *q = (current / d as u64) as u32;
+        remainder = current % d as u64;
+    }
+    trim_zero_limbs(&mut quotient);
+    (quotient, remainder as u32)
+}
+
+fn rem_small(a: &[u32], d: u32) -> u32 {
+    a.iter().rev().fold(0u64, |remainder, &x| {
+        ((remainder << LIMB_BITS) | x as u64) % d as u64
+    }) as u32
+}
+
+fn trimmed(a: &[u32]) -> &[u32] {
+    let len = a.len() - a.iter().rev().take_while(|&&x| x == 0).count();
+    &a[..len]
+}
+
+// Operand sizes, in limbs, from which the faster multiplication algorithms
+// take over.
+const KARATSUBA_THRESHOLD: usize = 48;
+const TOOM3_THRESHOLD: usize = 320;
+
+fn mul_limbs(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let (a, b) = (trimmed(a), trimmed(b));
+    let (a, b) = if a.len() < b.len() { (b, a) } else { (a, b) };
+
+    if b.is_empty() {
+        Vec::new()
+    } else if b.len() < KARATSUBA_THRESHOLD {
+        mul_schoolbook(a, b)
+    } else if a.len() >= 2 * b.len() {
+        // Split the longer operand so that the recursive algorithms always
+        // work on operands of similar size.
+        let mut result = Vec::with_capacity(a.len() + b.len());
+        for (i, chunk) in a.chunks(b.len()).enumerate() {
+            add_shifted_limbs(&mut result, &mul_limbs(chunk, b), i * b.len());
+        }
+        trim_zero_limbs(&mut result);
+        result
+    } else if b.len() < TOOM3_THRESHOLD {
+        mul_karatsuba(a, b)
+    } else {
+        mul_toom3(a, b)
+    }
+}
+
+fn mul_schoolbook(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut result = vec![0u32; a.len() + b.len()];
+
+    for (i, &x) in a.iter().enumerate() {
+        let mut carry = 0u64;
+        for (j, &y) in b.iter().enumerate() {
+            let product = x as u64 * y as u64 + result[i + j] as u64 + carry;
+            result[i + j] = product as u32;
+            carry = product >> LIMB_BITS;
+        }
+        result[i + b.len()] = carry as u32;
+    }
+
+    trim_zero_limbs(&mut result);
+    result
+}
+
+// (a1 x + a0)(b1 x + b0) = a1 b1 x^2 + ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) x + a0 b0
+fn mul_karatsuba(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let half = a.len().max(b.len()).div_ceil(2);
+    let (a0, a1) = a.split_at(half.min(a.len()));
+    let (b0, b1) = b.split_at(half.min(b.len()));
+
+    let z0 = mul_limbs(a0, b0);
+    let z2 = mul_limbs(a1, b1);
+
+    let mut a_sum = trimmed(a0).to_vec();
+    add_assign_limbs(&mut a_sum, a1);
+    let mut b_sum = trimmed(b0).to_vec();
+    add_assign_limbs(&mut b_sum, b1);
+
+    let mut z1 = mul_limbs(&a_sum, &b_sum);
+    sub_assign_limbs(&mut z1, &z0);
+    sub_assign_limbs(&mut z1, &z2);
+
+    let mut result = z0;
+    add_shifted_limbs(&mut result, &z1, half);
+    add_shifted_limbs(&mut result, &z2, 2 * half);
+    trim_zero_limbs(&mut result);
+    result
+}
+
+// Toom-Cook 3-way multiplication, evaluating at 0, 1, -1, -2 and infinity and
+// interpolating with Bodrato's sequence. Some of the intermediate values can be
+// negative, so they are kept as signed `BigInt`s.
+fn mul_toom3(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let k = a.len().max(b.len()).div_ceil(3);
+
+    let [a_0, a_1, a_m1, a_m2, a_inf] = toom3_evaluate(a, k);
+    let [b_0, b_1, b_m1, b_m2, b_inf] = toom3_evaluate(b, k);
+
+    let v_0 = a_0 * b_0;
+    let v_1 = a_1 * b_1;
+    let v_m1 = a_m1 * b_m1;
+    let v_m2 = a_m2 * b_m2;
+    let v_inf = a_inf * b_inf;
+
+    let r3 = exact_div_small(v_m2 - &v_1, 3);
+    let r1 = halve(v_1 - &v_m1);
+    let r2 = v_m1 - &v_0;
+    let r3 = halve(&r2 - r3) + &v_inf + &v_inf;
+    let r2 = r2 + &r1 - &v_inf;
+    let r1 = r1 - &r3;
+
+    let mut result = v_0.limbs;
+    for (i, coefficient) in [r1, r2, r3, v_inf].iter().enumerate() {
+        debug_assert!(!coefficient.is_negative());
+        add_shifted_limbs(&mut result, &coefficient.limbs, (i + 1) * k);
+    }
+    trim_zero_limbs(&mut result);
+    result
+}
+
+// Splits `x` into three parts of `k` limbs and evaluates the polynomial with
+// these coefficients at 0, 1, -1, -2 and infinity.
+fn toom3_evaluate(x: &[u32], k: usize) -> [BigInt; 5] {
+    let part = |i: usize| {
+        let start = (i * k).min(x.len());
+        let end = ((i + 1) * k).min(x.len());
+        BigInt::from_limbs(1, x[start..end].to_vec())
+    };
+
+    let p = part(0) + part(2);
+    let at_1 = &p + part(1);
+    let at_m1 = p - part(1);
+    let at_m2 = &at_m1 + part(2);
+    let at_m2 = &at_m2 + &at_m2 - part(0);
+
+    [part(0), at_1, at_m1, at_m2, part(2)]
+}
+
+fn exact_div_small(x: BigInt, d: u32) -> BigInt {
+    let (quotient, remainder) = div_rem_small(&x.limbs, d);
+    debug_assert_eq!(remainder, 0);
+    BigInt::from_limbs(x.sign, quotient)
+}
+
+fn halve(x: BigInt) -> BigInt {
+    BigInt::from_limbs(x.sign, shr_limbs(&x.limbs, 1))
+}
+
+fn shl_limbs(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+
+    let mut result = Vec::with_capacity(a.len() + 1);
+    let mut carry = 0;
+    for &x in a {
+        result.push((x << shift) | carry);
+        carry = x >> (LIMB_BITS - shift);
+    }
+    result.push(carry);
+    result
+}
+
+fn shr_limbs(a: &[u32], shift: u32) -> Vec<u32> {
+    if shift == 0 {
+        return a.to_vec();
+    }
+
+    let mut result = vec![0; a.len()];
+    for i in 0..a.len() {
+        let high = a.get(i + 1).map_or(0, |&x| x << (LIMB_BITS - shift));
+        result[i] = (a[i] >> shift) | high;
+    }
+    trim_zero_limbs(&mut result);
+    result
+}
+
+// Knuth's algorithm D (TAOCP vol. 2, 4.3.1).
+fn div_rem_limbs(a: &[u32], b: &[u32]) -> (Vec<u32>, Vec<u32>) {
+    if cmp_limbs(a, b) == Ordering::Less {
+        return (Vec::new(), a.to_vec());
+    }
+
+    if b.len() == 1 {
+        let (quotient, remainder) = div_rem_small(a, b[0]);
+        return (quotient, vec![remainder]);
+    }
+
+    // Normalize so that the top limb of the divisor has its high bit set,
+    // which keeps every estimated quotient limb at most two too big.
+    let shift = b[b.len() - 1].leading_zeros();
+    let v = shl_limbs(b, shift);
+    let v = &v[..b.len()];
+    let mut u = shl_limbs(a, shift);
+    if u.len() == a.len() {
+        u.push(0);
+    }
+
+    let n = v.len();
+    let m = u.len() - n;
+    let base = 1u64 << LIMB_BITS;
+    let mut quotient = vec![0u32; m];
+
+    for j in (0..m).rev() {
+        let top = ((u[j + n] as u64) << LIMB_BITS) | u[j + n - 1] as u64;
+        let mut qhat = top / v[n - 1] as u64;
+        let mut rhat = top % v[n - 1] as u64;
+
+        while qhat >= base || qhat * v[n - 2] as u64 > ((rhat << LIMB_BITS) | u[j + n - 2] as u64) {
+            qhat -= 1;
+            rhat += v[n - 1] as u64;
+            if rhat >= base {
+                break;
+            }
+        }
+
+        let mut borrow = 0i64;
+        for i in 0..n {
+            let product = qhat * v[i] as u64;
+            let diff = u[i + j] as i64 - borrow - (product & 0xFFFF_FFFF) as i64;
+            u[i + j] = diff as u32;
+            borrow = (product >> LIMB_BITS) as i64 - (diff >> LIMB_BITS);
+        }
+        let diff = u[j + n] as i64 - borrow;
+        u[j + n] = diff as u32;
+
+        if diff < 0 {
+            qhat -= 1;
+            let mut carry = 0u64;
+            for i in 0..n {
+                let sum = u[i + j] as u64 + v[i] as u64 + carry;
+                u[i + j] = sum as u32;
+                carry = sum >> LIMB_BITS;
+            }
+            u[j + n] = u[j + n].wrapping_add(carry as u32);
+        }
+
+        quotient[j] = qhat as u32;
+    }
+
+    trim_zero_limbs(&mut quotient);
+    (quotient, shr_limbs(&u[..n], shift))
+}
+
+// One step of a 64-bit LCG, for cheap reproducible pseudo-random numbers.
+// The high bits are the random ones.
+fn next_random(seed: &mut u64) -> u64 {
+    *seed = seed
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *seed
+}
+
+#[cfg(test)]
+pub(crate) fn random_limbs(seed: &mut u64, len: usize) -> Vec<u32> {
+    (0..len)
+        .map(|_| {
+            let r = next_random(seed);
+            // Mix in runs of all-zero and all-one limbs to exercise the carries.
+            match (r >> 60) % 8 {
+                0 => 0,
+                1 => u32::MAX,
+                _ => (r >> 32) as u32,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+pub(crate) fn random_bigint(seed: &mut u64, max_limbs: usize) -> BigInt {
+    let len = (next_random(seed) >> 33) as usize % (max_limbs + 1);
+    let sign = if next_random(seed) >> 63 == 1 { -1 } else { 1 };
+    BigInt::from_limbs(sign, random_limbs(seed, len))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mul_karatsuba, mul_limbs, mul_schoolbook, mul_toom3, next_random, random_bigint,
+        random_limbs, BigInt, FromPartsError, ParseErrorKind,
+    };
+    use std::cmp::Ordering;
+    use std::convert::TryFrom;
+    use std::ops::{Add, Sub};
+    use std::str::FromStr;
+
+    impl BigInt {
+        fn from_digits(sign: i8, digits: Vec<u8>) -> BigInt {
+            BigInt::from_parts(sign, &digits).unwrap()
+        }
+
+        fn digits(&self) -> Vec<u8> {
+            let digits = self.to_string();
+            digits
+                .trim_start_matches('-')
+                .bytes()
+                .map(|d| d - b'0')
+                .collect()
+        }
+    }
+
+    const SAMPLES: [i128; 16] = [
+        0,
+        1,
+        -1,
+        7,
+        -7,
+        10,
+        -13,
+        99,
+        1000,
+        -123456789,
+        9223372036854775807,
+        -9223372036854775808,
+        18446744073709551616,
+        -85070591730234615865843651857942052864,
+        i128::MAX,
+        i128::MIN,
+    ];
+
+    #[test]
+    #[allow(clippy::bool_assert_comparison)]
+    fn pos_neg() {
+        let a = vec![1, 2, 3];
+        let b = vec![1, 2, 3];
+
+        let pos = BigInt::from_digits(1, a);
+        let neg = BigInt::from_digits(-1, b);
+
+        assert_eq!(pos.is_negative(), false);
+        assert_eq!(neg.is_negative(), true);
+        assert_eq!(pos.is_positive(), true);
+        assert_eq!(neg.is_positive(), false);
+
+        let c = vec![0];
+        let zero = BigInt::from_digits(1, c);
+        assert_eq!(zero.is_positive(), false);
+        assert_eq!(zero.is_negative(), false);
+    }
+
+    #[test]
+    fn test_from_string() {
+        let a = BigInt::from_str("").unwrap();
+        assert_eq!(a.digits(), vec![0]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("123").unwrap();
+        assert_eq!(a.digits(), vec![1, 2, 3]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("-123").unwrap();
+        assert_eq!(a.digits(), vec![1, 2, 3]);
+        assert_eq!(a.sign, -1);
+
+        let a = BigInt::from_str("+123").unwrap();
+        assert_eq!(a.digits(), vec![1, 2, 3]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("-0").unwrap();
+        assert_eq!(a, BigInt::new());
+        assert_eq!(a.digits(), vec![0]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("+0").unwrap();
+        assert_eq!(a, BigInt::new());
+        assert_eq!(a.digits(), vec![0]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("+00000000013").unwrap();
+        assert_eq!(a.digits(), vec![1, 3]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("+0000").unwrap();
+        assert_eq!(a, BigInt::new());
+        assert_eq!(a.digits(), vec![0]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("-0000").unwrap();
+        assert_eq!(a, BigInt::new());
+        assert_eq!(a.digits(), vec![0]);
+        assert_eq!(a.sign, 1);
+
+        let a = BigInt::from_str("0").unwrap();
+        assert_eq!(a.digits(), vec![0]);
+        assert_eq!(a.sign, 1);
+
+        assert!(BigInt::from_str("abc").is_err());
+        assert!(BigInt::from_str("1+2").is_err());
+        assert!(BigInt::from_str("   12").is_err());
+        assert!(BigInt::from_str("+1   ").is_err());
+        assert!(BigInt::from_str("-").is_err());
+    }
+
+    #[test]
+    fn test_order() {
+        let a = BigInt::from_str("-1").unwrap();
+        let b = BigInt::from_str("2").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(b.cmp(&a), Ordering::Greater);
+
+        let a = BigInt::from_str("1").unwrap();
+        let b = BigInt::from_str("2").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Less);
+
+        let a = BigInt::from_str("-1").unwrap();
+        let b = BigInt::from_str("-2").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Greater);
+
+        let a = BigInt::from_str("10").unwrap();
+        let b = BigInt::from_str("2").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Greater);
+        assert_eq!(b.cmp(&a), Ordering::Less);
+
+        let a = BigInt::from_str("-10").unwrap();
+        let b = BigInt::from_str("-2").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Less);
+        assert_eq!(b.cmp(&a), Ordering::Greater);
+
+        let a = BigInt::from_str("-0").unwrap();
+        let b = BigInt::from_str("+0").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+
+        let a = BigInt::from_str("0").unwrap();
+        let b = BigInt::from_str("+0").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+
+        let a = BigInt::from_str("0").unwrap();
+        let b = BigInt::from_str("50").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Less);
+
+        let a = BigInt::from_str("+5000").unwrap();
+        let b = BigInt::from_str("5000").unwrap();
+        assert_eq!(a.cmp(&b), Ordering::Equal);
+    }
+
+    #[test]
+    fn test_add_and_sub() {
+        assert_eq!(
+            BigInt::from_str("21325")
+                .unwrap()
+                .add(BigInt::from_str("12").unwrap())
+                .digits(),
+            vec![2, 1, 3, 3, 7]
+        );
+        assert_eq!(
+            BigInt::from_str("123456")
+                .unwrap()
+                .add(BigInt::from_str("444").unwrap())
+                .digits(),
+            vec![1, 2, 3, 9, 0, 0]
+        );
+        assert_eq!(
+            BigInt::from_str("15")
+                .unwrap()
+                .add(BigInt::from_str("15").unwrap())
+                .digits(),
+            vec![3, 0]
+        );
+        assert_eq!(
+            BigInt::from_str("+25")
+                .unwrap()
+                .add(BigInt::from_str("17").unwrap())
+                .digits(),
+            vec![4, 2]
+        );
+        assert_eq!(
+            BigInt::from_str("-25")
+                .unwrap()
+                .add(BigInt::from_str("-17").unwrap()),
+            BigInt::from_digits(-1, vec![4, 2])
+        );
+        assert_eq!(
+            BigInt::from_str("21325")
+                .unwrap()
+                .add(BigInt::from_str("-12").unwrap())
+                .digits(),
+            vec![2, 1, 3, 1, 3]
+        );
+        assert_eq!(
+            BigInt::from_str("25")
+                .unwrap()
+                .add(BigInt::from_str("-12").unwrap())
+                .digits(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            BigInt::from_str("325")
+                .unwrap()
+                .add(BigInt::from_str("-98").unwrap())
+                .digits(),
+            vec![2, 2, 7]
+        );
+        assert_eq!(
+            BigInt::from_str("25")
+                .unwrap()
+                .add(BigInt::from_str("-25").unwrap())
+                .digits(),
+            vec![0]
+        );
+        assert_eq!(
+            BigInt::from_str("100")
+                .unwrap()
+                .add(BigInt::from_str("-99").unwrap()),
+            BigInt::from_digits(1, vec![1])
+        );
+        assert_eq!(
+            BigInt::from_str("-25")
+                .unwrap()
+                .add(BigInt::from_str("25").unwrap()),
+            BigInt::from_digits(1, vec![0])
+        );
+
+        assert_eq!(
+            BigInt::from_str("17")
+                .unwrap()
+                .sub(BigInt::from_str("19").unwrap()),
+            BigInt::from_digits(-1, vec![2])
+        );
+        assert_eq!(
+            BigInt::from_str("325")
+                .unwrap()
+                .sub(BigInt::from_str("98").unwrap())
+                .digits(),
+            vec![2, 2, 7]
+        );
+        assert_eq!(
+            BigInt::from_str("-25")
+                .unwrap()
+                .sub(BigInt::from_str("-25").unwrap())
+                .digits(),
+            vec![0]
+        );
+        assert_eq!(
+            BigInt::from_str("325")
+                .unwrap()
+                .sub(BigInt::from_str("98").unwrap())
+                .digits(),
+            vec![2, 2, 7]
+        );
+        assert_eq!(
+            bigint("156483998155463") + bigint("15482265487796"),
+            bigint("171966263643259")
+        );
+        assert_eq!(bigint("1298975") + bigint("6665"), bigint("1305640"));
+        assert_eq!(bigint("1298975") + bigint("6665"), bigint("1305640"));
+        assert_eq!(bigint("752") - bigint("354"), bigint("398"));
+        assert_eq!(
+            bigint("340282366920938463463374607431768211456")
+                + bigint("565784967567542754765764575735654656546546555"),
+            bigint("565785307849909675704228039110262088314758011")
+        );
+        assert_eq!(bigint("340282366920938463463374607431768211456") - bigint("565784967567542754765764575735654656546546555"), bigint("-565784627285175833827301112361047224778335099"));
+    }
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_basic() {
+        assert_eq!(BigInt::new(), bigint("0"));
+        assert!(BigInt::from_str("foobar").is_err());
+
+        assert!(bigint("1").is_positive());
+        assert!(bigint("-1").is_negative());
+
+        assert_eq!(bigint("123") + bigint("456"), bigint("579"));
+        assert_eq!(bigint("579") - bigint("456"), bigint("123"));
+
+        assert!(bigint("123") > bigint("122"));
+    }
+
+    #[test]
+    fn test_mul() {
+        assert_eq!(bigint("12") * bigint("12"), bigint("144"));
+        assert_eq!(bigint("-12") * bigint("12"), bigint("-144"));
+        assert_eq!(bigint("-12") * bigint("-12"), bigint("144"));
+        assert_eq!(bigint("-12") * bigint("0"), BigInt::new());
+        assert_eq!(bigint("0") * bigint("-5"), BigInt::new());
+        assert_eq!(
+            bigint("340282366920938463463374607431768211456")
+                * bigint("-340282366920938463463374607431768211456"),
+            bigint(
+                "-115792089237316195423570985008687907853269984665640564039457584007913129639936"
+            )
+        );
+
+        for &a in SAMPLES.iter() {
+            for &b in SAMPLES.iter() {
+                if let Some(product) = a.checked_mul(b) {
+                    assert_eq!(
+                        bigint(&a.to_string()) * bigint(&b.to_string()),
+                        bigint(&product.to_string())
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_div_and_rem() {
+        assert_eq!(bigint("7") / bigint("2"), bigint("3"));
+        assert_eq!(bigint("-7") / bigint("2"), bigint("-3"));
+        assert_eq!(bigint("7") / bigint("-2"), bigint("-3"));
+        assert_eq!(bigint("-7") / bigint("-2"), bigint("3"));
+        assert_eq!(bigint("7") % bigint("2"), bigint("1"));
+        assert_eq!(bigint("-7") % bigint("2"), bigint("-1"));
+        assert_eq!(bigint("7") % bigint("-2"), bigint("1"));
+        assert_eq!(bigint("-7") % bigint("-2"), bigint("-1"));
+
+        let (q, r) = bigint("-6").div_rem(&bigint("3")).unwrap();
+        assert_eq!(q, bigint("-2"));
+        assert_eq!(r, BigInt::new());
+        assert_eq!(r.sign, 1);
+
+        let (q, r) = bigint("-2").div_rem(&bigint("3")).unwrap();
+        assert_eq!(q, BigInt::new());
+        assert_eq!(q.sign, 1);
+        assert_eq!(r, bigint("-2"));
+
+        assert!(bigint("5").div_rem(&bigint("0")).is_err());
+        assert!(bigint("0").div_rem(&bigint("-0")).is_err());
+
+        let boxed: Box<dyn std::error::Error> =
+            bigint("5").div_rem(&bigint("0")).unwrap_err().into();
+        assert_eq!(boxed.to_string(), "division by zero");
+
+        for &a in SAMPLES.iter() {
+            for &b in SAMPLES.iter() {
+                if let (Some(q), Some(r)) = (a.checked_div(b), a.checked_rem(b)) {
+                    let (bq, br) = bigint(&a.to_string())
+                        .div_rem(&bigint(&b.to_string()))
+                        .unwrap();
+                    assert_eq!(bq, bigint(&q.to_string()));
+                    assert_eq!(br, bigint(&r.to_string()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_div_by_zero() {
+        let _ = bigint("1") / BigInt::new();
+    }
+
+    // Deterministic pseudo-random decimal strings, so the tests stay reproducible.
+    fn random_digits(seed: &mut u64, len: usize) -> String {
+        let mut digits = String::with_capacity(len);
+        for i in 0..len {
+            let digit = (next_random(seed) >> 33) % 10;
+            digits.push((b'0' + if i == 0 { digit % 9 + 1 } else { digit } as u8) as char);
+        }
+        digits
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(BigInt::new().to_string(), "0");
+        assert_eq!(bigint("-0").to_string(), "0");
+        assert_eq!(bigint("+0001000000000").to_string(), "1000000000");
+        assert_eq!(bigint("-999999999").to_string(), "-999999999");
+        assert_eq!(
+            bigint("-340282366920938463463374607431768211456").to_string(),
+            "-340282366920938463463374607431768211456"
+        );
+
+        let mut seed = 42;
+        for len in 1..200 {
+            let digits = random_digits(&mut seed, len);
+            assert_eq!(bigint(&digits).to_string(), digits);
+        }
+    }
+
+    #[test]
+    fn test_div_rem_large() {
+        let mut seed = 7;
+        for len in [10, 30, 64, 100, 257].iter() {
+            for divisor_len in [1, 9, 10, 19, 20, 40, 99].iter() {
+                let a = bigint(&random_digits(&mut seed, *len));
+                let b = bigint(&format!("-{}", random_digits(&mut seed, *divisor_len)));
+                let (q, r) = a.div_rem(&b).unwrap();
+
+                assert!(!r.is_negative());
+                assert!(bigint(&r.to_string()) < bigint(&b.to_string()[1..]));
+                assert_eq!(q * b + r, a);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fast_mul_matches_schoolbook() {
+        let mut seed = 3;
+        for &(a_len, b_len) in [
+            (2, 2),
+            (3, 2),
+            (5, 5),
+            (31, 32),
+            (32, 32),
+            (33, 40),
+            (47, 47),
+            (47, 48),
+            (48, 48),
+            (49, 48),
+            (49, 49),
+            (64, 64),
+            (100, 57),
+            (159, 160),
+            (160, 160),
+            (161, 200),
+            (300, 250),
+            (319, 319),
+            (320, 319),
+            (320, 320),
+            (321, 320),
+            (321, 321),
+            (481, 480),
+            (700, 100),
+            (1000, 999),
+            // Lopsided, so that `mul_limbs` splits the longer operand.
+            (96, 48),
+            (97, 47),
+            (150, 49),
+            (640, 320),
+            (700, 319),
+            (1000, 321),
+        ]
+        .iter()
+        {
+            for _ in 0..3 {
+                let a = random_limbs(&mut seed, a_len);
+                let b = random_limbs(&mut seed, b_len);
+                let expected = mul_schoolbook(&a, &b);
+
+                assert_eq!(mul_karatsuba(&a, &b), expected);
+                assert_eq!(mul_toom3(&a, &b), expected);
+                assert_eq!(mul_limbs(&a, &b), expected);
+                assert_eq!(mul_limbs(&b, &a), expected);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fast_mul_edge_cases() {
+        let ones = vec![u32::MAX; 500];
+        let expected = mul_schoolbook(&ones, &ones);
+        assert_eq!(mul_karatsuba(&ones, &ones), expected);
+        assert_eq!(mul_toom3(&ones, &ones), expected);
+
+        let mut sparse = vec![0; 400];
+        sparse[0] = 1;
+        sparse[399] = 1;
+        let expected = mul_schoolbook(&sparse, &ones);
+        assert_eq!(mul_karatsuba(&sparse, &ones), expected);
+        assert_eq!(mul_toom3(&sparse, &ones), expected);
+        assert_eq!(mul_limbs(&sparse, &[]), Vec::<u32>::new());
+
+        let mut seed = 11;
+        let a = bigint(&random_digits(&mut seed, 5000));
+        let b = bigint(&format!("-{}", random_digits(&mut seed, 4000)));
+        let (a_copy, b_copy) = (bigint(&a.to_string()), bigint(&b.to_string()));
+        let product = a * b;
+        assert!(product.is_negative());
+        assert_eq!(product.div_rem(&b_copy).unwrap(), (a_copy, BigInt::new()));
+    }
+
+    #[test]
+    fn test_ref_ops() {
+        for &a in SAMPLES.iter() {
+            for &b in SAMPLES.iter() {
+                let (x, y) = (bigint(&a.to_string()), bigint(&b.to_string()));
+
+                if let Some(sum) = a.checked_add(b) {
+                    let sum = bigint(&sum.to_string());
+                    assert_eq!(&x + &y, sum);
+                    assert_eq!(bigint(&a.to_string()) + &y, sum);
+                    assert_eq!(&x + bigint(&b.to_string()), sum);
+                }
+
+                if let Some(difference) = a.checked_sub(b) {
+                    let difference = bigint(&difference.to_string());
+                    assert_eq!(&x - &y, difference);
+                    assert_eq!(bigint(&a.to_string()) - &y, difference);
+                    assert_eq!(&x - bigint(&b.to_string()), difference);
+                }
+
+                if let Some(product) = a.checked_mul(b) {
+                    let product = bigint(&product.to_string());
+                    assert_eq!(&x * &y, product);
+                    assert_eq!(bigint(&a.to_string()) * &y, product);
+                    assert_eq!(&x * bigint(&b.to_string()), product);
+                }
+
+                if let (Some(q), Some(r)) = (a.checked_div(b), a.checked_rem(b)) {
+                    assert_eq!(&x / &y, bigint(&q.to_string()));
+                    assert_eq!(&x % &y, bigint(&r.to_string()));
+                    assert_eq!(bigint(&a.to_string()) / &y, bigint(&q.to_string()));
+                    assert_eq!(&x % bigint(&b.to_string()), bigint(&r.to_string()));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_assign_ops() {
+        let mut a = bigint("100");
+        a += bigint("-150");
+        assert_eq!(a, bigint("-50"));
+        a -= &bigint("-50");
+        assert_eq!(a, BigInt::new());
+        assert_eq!(a.sign, 1);
+        a -= bigint("7");
+        a *= &bigint("-6");
+        assert_eq!(a, bigint("42"));
+        a /= bigint("4");
+        assert_eq!(a, bigint("10"));
+        a %= &bigint("-3");
+        assert_eq!(a, bigint("1"));
+        a += &bigint("18446744073709551615");
+        assert_eq!(a, bigint("18446744073709551616"));
+    }
+
+    #[test]
+    fn test_assign_reuses_buffer() {
+        let mut a = bigint("-340282366920938463463374607431768211455");
+        let ptr = a.limbs.as_ptr();
+
+        a += &bigint("12345678901234567890");
+        assert_eq!(a.limbs.as_ptr(), ptr);
+        a -= &bigint("987654321");
+        assert_eq!(a.limbs.as_ptr(), ptr);
+        a += &bigint("340282366920938463463374607431768211455");
+        assert_eq!(a.limbs.as_ptr(), ptr);
+        assert_eq!(a, bigint("12345678900246913569"));
+        a -= &bigint("12345678900246913570");
+        assert_eq!(a.limbs.as_ptr(), ptr);
+        assert_eq!(a, bigint("-1"));
+    }
+
+    #[test]
+    fn test_neg() {
+        assert_eq!(-bigint("5"), bigint("-5"));
+        assert_eq!(-&bigint("-5"), bigint("5"));
+        assert_eq!(-BigInt::new(), BigInt::new());
+        assert_eq!((-BigInt::new()).sign, 1);
+        assert_eq!(
+            -(-bigint("123456789012345678901234567890")),
+            bigint("123456789012345678901234567890")
+        );
+    }
+
+    #[test]
+    fn test_from_parts() {
+        assert_eq!(BigInt::from_parts(1, &[1, 2, 3]), Ok(bigint("123")));
+        assert_eq!(BigInt::from_parts(-1, &[0, 0, 4, 2]), Ok(bigint("-42")));
+        assert_eq!(BigInt::from_parts(-1, &[0, 0]), Ok(BigInt::new()));
+        assert_eq!(BigInt::from_parts(0, &[0]), Ok(BigInt::new()));
+        assert_eq!(BigInt::from_parts(1, &[]), Ok(BigInt::new()));
+        assert_eq!(
+            (-1..=1)
+                .map(|s| BigInt::from_parts(s, &[]).unwrap().sign)
+                .collect::<Vec<_>>(),
+            [1, 1, 1]
+        );
+
+        let digits: Vec<u8> = (0..100).map(|i| (i * 7 % 10) as u8).collect();
+        let expected: String = digits[1..].iter().map(|d| (b'0' + d) as char).collect();
+        assert_eq!(
+            BigInt::from_parts(-1, &digits),
+            Ok(bigint(&format!("-{}", expected)))
+        );
+
+        assert_eq!(
+            BigInt::from_parts(2, &[1]),
+            Err(FromPartsError::InvalidSign(2))
+        );
+        assert_eq!(
+            BigInt::from_parts(0, &[1]),
+            Err(FromPartsError::InvalidSign(0))
+        );
+        assert_eq!(
+            BigInt::from_parts(1, &[1, 10, 11]),
+            Err(FromPartsError::InvalidDigit(1))
+        );
+        assert_eq!(
+            FromPartsError::InvalidDigit(1).to_string(),
+            "invalid decimal digit at index 1"
+        );
+    }
+
+    #[test]
+    fn test_signum() {
+        assert_eq!(bigint("-12345678901234567890").signum(), -1);
+        assert_eq!(bigint("-0").signum(), 0);
+        assert_eq!((bigint("7") - bigint("7")).signum(), 0);
+        assert_eq!(bigint("1").signum(), 1);
+    }
+
+    fn assert_canonical(x: &BigInt, operation: &str) {
+        assert!(
+            x.sign == 1 || x.sign == -1,
+            "{}: sign {}",
+            operation,
+            x.sign
+        );
+        assert_ne!(
+            x.limbs.last(),
+            Some(&0),
+            "{}: trailing zero limb",
+            operation
+        );
+        assert!(
+            !x.limbs.is_empty() || x.sign == 1,
+            "{}: negative zero",
+            operation
+        );
+    }
+
+    // Runs every public operation on random operands, biased towards values
+    // that cancel out, and checks the representation of every result.
+    #[test]
+    fn test_fuzz_canonical() {
+        let mut seed = 2024;
+        for _ in 0..400 {
+            let a = random_bigint(&mut seed, 5);
+            let b = match (next_random(&mut seed) >> 33) % 4 {
+                0 => -&a,
+                1 => a.clone(),
+                _ => random_bigint(&mut seed, 5),
+            };
+            let small = (next_random(&mut seed) >> 33) as usize % 100;
+
+            let mut results = vec![
+                ("a + b", &a + &b),
+                ("a + -a", a.clone() + -&a),
+                ("a - b", a.clone() - b.clone()),
+                ("b - a", &b - a.clone()),
+                ("a - a", &a - &a),
+                ("a * b", &a * &b),
+                ("-a", -a.clone()),
+                ("!a", !&a),
+                ("a & b", &a & &b),
+                ("a | b", &a | &b),
+                ("a ^ b", &a ^ &b),
+                ("a ^ a", &a ^ &a),
+                ("a << k", &a << small),
+                ("a >> k", &a >> small),
+                ("a.pow(k)", a.pow(small as u32 % 5)),
+                ("gcd", a.gcd(&b)),
+                ("lcm", a.lcm(&b)),
+                ("isqrt", a.abs_value().isqrt()),
+                ("cbrt", a.nth_root(3)),
+                ("sum", [&a, &b, &-&a].iter().copied().sum()),
+                ("product", [&a, &b].iter().copied().product()),
+                ("parse", bigint(&a.to_string())),
+                (
+                    "hex",
+                    BigInt::from_str_radix(&a.to_str_radix(16), 16).unwrap(),
+                ),
+                ("bytes be", BigInt::from_bytes_be(&a.to_bytes_be())),
+                (
+                    "signed bytes",
+                    BigInt::from_signed_bytes_le(&a.to_signed_bytes_le()),
+                ),
+                ("varint", BigInt::from_varint(&a.to_varint()).unwrap().0),
+                (
+                    "i128",
+                    BigInt::from(i128::try_from(&(&a >> 200)).unwrap_or(-1)),
+                ),
+            ];
+            if !b.limbs.is_empty() {
+                let (quotient, remainder) = a.div_rem(&b).unwrap();
+                results.push(("a / b", quotient));
+                results.push(("a % b", remainder));
+                results.push(("mod_floor", a.mod_floor(&b)));
+                results.push(("mod_pow", a.mod_pow(&BigInt::from(small), &b)));
+            }
+
+            let mut x = a.clone();
+            x += &b;
+            x -= &a;
+            x -= &b;
+            results.push(("assign ops", x));
+            let mut x = a.clone();
+            x.set_bit(small as u64, false);
+            x.set_bit(small as u64 + 1, true);
+            results.push(("set_bit", x));
+
+            let digits: Vec<u8> = (0..small % 8).map(|i| (i * small % 10) as u8).collect();
+            let sign = if a.is_negative() { -1 } else { 1 };
+            results.push(("from_parts", BigInt::from_parts(sign, &digits).unwrap()));
+
+            for (operation, result) in results.iter() {
+                assert_canonical(result, operation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_zero_and_one() {
+        assert_eq!(BigInt::zero(), bigint("0"));
+        assert_eq!(BigInt::one(), bigint("1"));
+        assert_eq!(BigInt::default(), BigInt::zero());
+        assert!(bigint("-0").is_zero() && !bigint("1").is_zero());
+        assert!(bigint("1").is_one() && !bigint("-1").is_one());
+        assert!(!bigint("4294967297").is_one());
+    }
+
+    #[test]
+    fn test_hash_and_clone() {
+        use std::collections::HashMap;
+
+        // Every way of making zero has to land in the same bucket.
+        let mut counts = HashMap::new();
+        let zeros = [
+            bigint("0"),
+            bigint("-0"),
+            bigint("5") - bigint("5"),
+            -BigInt::new(),
+            bigint("-123456789012345678901") % bigint("123456789012345678901"),
+        ];
+        for zero in zeros.iter() {
+            *counts.entry(zero.clone()).or_insert(0) += 1;
+        }
+        *counts.entry(bigint("-7")).or_insert(0) += 1;
+        *counts.entry(bigint("7")).or_insert(0) += 1;
+        assert_eq!(counts.len(), 3);
+        assert_eq!(counts[&BigInt::zero()], 5);
+
+        let a = bigint("-98765432109876543210");
+        let mut b = a.clone();
+        b += &BigInt::one();
+        assert_eq!(a, bigint("-98765432109876543210"));
+        assert_eq!(b, bigint("-98765432109876543209"));
+    }
+
+    #[test]
+    fn test_sum_and_product() {
+        let values: Vec<BigInt> = (1..=30).map(|i| BigInt::from(i * i - 100)).collect();
+        let expected = values.iter().fold(BigInt::zero(), |acc, x| acc + x);
+        assert_eq!(values.iter().sum::<BigInt>(), expected);
+
+        let factorial: BigInt = (1..=25u32).map(BigInt::from).product();
+        assert_eq!(factorial, bigint("15511210043330985984000000"));
+        let product: BigInt = values.iter().take(5).product();
+        assert_eq!(product, bigint("-5448643200"));
+
+        assert_eq!(
+            Vec::<BigInt>::new().into_iter().sum::<BigInt>(),
+            BigInt::zero()
+        );
+        assert_eq!(
+            Vec::<BigInt>::new().iter().product::<BigInt>(),
+            BigInt::one()
+        );
+        assert_eq!(values.into_iter().sum::<BigInt>(), expected);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = BigInt::from_str("0x").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::Empty);
+        assert_eq!(err.position(), 2);
+        assert_eq!(err.character(), None);
+
+        let err = BigInt::from_str("-").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::LoneSign);
+        assert_eq!(err.position(), 0);
+        assert_eq!(err.character(), Some('-'));
+
+        let err = BigInt::from_str("-0x").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::Empty);
+        assert_eq!(err.position(), 3);
+
+        let err = BigInt::from_str("+1   ").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit);
+        assert_eq!(err.position(), 2);
+        assert_eq!(err.character(), Some(' '));
+
+        let err = BigInt::from_str("12€4").unwrap_err();
+        assert_eq!(err.position(), 2);
+        assert_eq!(err.character(), Some('€'));
+
+        let err = BigInt::from_str("0xfg").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit);
+        assert_eq!(err.position(), 3);
+        assert_eq!(err.character(), Some('g'));
+
+        let mut digits = "7".repeat(2000);
+        digits.replace_range(1234..1235, "x");
+        let err = BigInt::from_str(&digits).unwrap_err();
+        assert_eq!(err.position(), 1234);
+        assert_eq!(err.character(), Some('x'));
+
+        let err = BigInt::from_str_radix("10", 40).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidRadix);
+        let err = BigInt::from_str_radix("", 16).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::Empty);
+        let err = BigInt::from_str_radix("+", 16).unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::LoneSign);
+        let err = BigInt::from_str_radix("-102", 2).unwrap_err();
+        assert_eq!((err.position(), err.character()), (3, Some('2')));
+    }
+
+    #[test]
+    fn test_parse_error_display() {
+        let message = |s: &str| BigInt::from_str(s).unwrap_err().to_string();
+        assert_eq!(message("-0x"), "no digits to parse at byte 3");
+        assert_eq!(message("+"), "sign without digits at byte 0");
+        assert_eq!(message("12a"), "invalid digit 'a' at byte 2");
+        assert_eq!(
+            BigInt::from_str_radix("1", 0).unwrap_err().to_string(),
+            "radix must be in the range 2..=36"
+        );
+
+        let err: Box<dyn std::error::Error> = Box::new(BigInt::from_str("x").unwrap_err());
+        assert_eq!(err.to_string(), "invalid digit 'x' at byte 0");
+    }
+}
@@ -0,0 +1,375 @@
+// Regional conventions for writing out digits: which character separates
+// the groups, how big the groups are and which digit characters to use.
+// `BigUint::delimited_with` writes a number this way and
+// `BigUint::from_delimited` reads it back.
+
+use crate::format::NumberFormat;
+use crate::{radix, BigInt, BigUint, ParseError, ParseErrorKind};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Grouping {
+    // 1,234,567
+    Thousands,
+    // Lakh and crore, 12,34,567: three digits at the bottom, two above.
+    Indian,
+}
+
+impl Grouping {
+    // Sizes of the lowest group and of the ones above it.
+    fn sizes(self) -> (usize, usize) {
+        match self {
+            Grouping::Thousands => (3, 3),
+            Grouping::Indian => (3, 2),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DigitShape {
+    Latin,
+    // ٠١٢٣٤٥٦٧٨٩
+    ArabicIndic,
+    // ०१२३४५६७८९
+    Devanagari,
+}
+
+impl DigitShape {
+    fn zero(self) -> char {
+        match self {
+            DigitShape::Latin => '0',
+            DigitShape::ArabicIndic => '\u{660}',
+            DigitShape::Devanagari => '\u{966}',
+        }
+    }
+
+    fn shape(self, digit: char) -> char {
+        match digit.to_digit(10) {
+            Some(value) => std::char::from_u32(self.zero() as u32 + value).unwrap(),
+            None => digit,
+        }
+    }
+
+    fn value(self, c: char) -> Option<u32> {
+        (c as u32)
+            .checked_sub(self.zero() as u32)
+            .filter(|&value| value < 10)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberLocale {
+    separator: char,
+    grouping: Grouping,
+    digits: DigitShape,
+}
+
+impl Default for NumberLocale {
+    fn default() -> Self {
+        NumberLocale::english()
+    }
+}
+
+impl NumberLocale {
+    pub fn new(separator: char, grouping: Grouping, digits: DigitShape) -> Self {
+        NumberLocale {
+            separator,
+            grouping,
+            digits,
+        }
+    }
+
+    // 1,234,567
+    pub fn english() -> Self {
+        NumberLocale::new(',', Grouping::Thousands, DigitShape::Latin)
+    }
+
+    // 1.234.567
+    pub fn german() -> Self {
+        NumberLocale::new('.', Grouping::Thousands, DigitShape::Latin)
+    }
+
+    // Thin spaces, as recommended by the SI brochure.
+    pub fn si() -> Self {
+        NumberLocale::new('\u{2009}', Grouping::Thousands, DigitShape::Latin)
+    }
+
+    // 12,34,567
+    pub fn indian() -> Self {
+        NumberLocale::new(',', Grouping::Indian, DigitShape::Latin)
+    }
+
+    // १२,३४,५६७
+    pub fn hindi() -> Self {
+        NumberLocale::new(',', Grouping::Indian, DigitShape::Devanagari)
+    }
+
+    // ١٬٢٣٤٬٥٦٧ with the Arabic thousands separator.
+    pub fn arabic() -> Self {
+        NumberLocale::new('\u{66c}', Grouping::Thousands, DigitShape::ArabicIndic)
+    }
+
+    pub fn separator(&self) -> char {
+        self.separator
+    }
+
+    pub fn grouping(&self) -> Grouping {
+        self.grouping
+    }
+
+    pub fn digits(&self) -> DigitShape {
+        self.digits
+    }
+
+    pub(crate) fn format(&self, digits: &str) -> String {
+        let (first, rest) = self.grouping.sizes();
+        let grouped = NumberFormat::new()
+            .with_separator(self.separator)
+            .with_groups(first, rest)
+            .group(digits);
+        grouped.chars().map(|c| self.digits.shape(c)).collect()
+    }
+}
+
+impl BigUint {
+    // Parses digits written the way `locale` writes them, after an optional
+    // '+' as `from_str` takes. All-Latin digits are accepted as well, and so
+    // is input with no separators at all, but any separator has to be where
+    // the locale would put one.
+    pub fn from_delimited(s: &str, locale: &NumberLocale) -> Result<BigUint, ParseError> {
+        if s.starts_with('-') {
+            return Err(ParseError::new(ParseErrorKind::InvalidDigit, 0, Some('-')));
+        }
+        let n = BigInt::from_delimited(s, locale)?;
+        Ok(BigUint::from_limbs(n.limbs))
+    }
+}
+
+impl BigInt {
+    // Like `BigUint::from_delimited`, after an optional sign.
+    pub fn from_delimited(s: &str, locale: &NumberLocale) -> Result<BigInt, ParseError> {
+        let (sign, digits) = radix::split_sign(s)?;
+        let limbs = parse_delimited(digits, locale, s.len() - digits.len())?;
+        Ok(BigInt::from_limbs(sign, limbs))
+    }
+}
+
+fn parse_delimited(s: &str, locale: &NumberLocale, offset: usize) -> Result<Vec<u32>, ParseError> {
+    // Every group is its digit count and the byte offset of the separator
+    // in front of it.
+    let mut values = Vec::with_capacity(s.len());
+    let mut groups = vec![(0, None)];
+    // Whether the digits are Latin, set by the first one. The locale's
+    // digits and Latin ones can't be mixed.
+    let mut latin = None;
+    for (i, c) in s.char_indices() {
+        if c == locale.separator {
+            groups.push((0, Some(offset + i)));
+            continue;
+        }
+
+        let invalid = || ParseError::new(ParseErrorKind::InvalidDigit, offset + i, Some(c));
+        let (value, is_latin) = match (c.to_digit(10), locale.digits.value(c)) {
+            (Some(value), _) => (value, true),
+            (None, Some(value)) => (value, false),
+            (None, None) => return Err(invalid()),
+        };
+        if *latin.get_or_insert(is_latin) != is_latin {
+            return Err(invalid());
+        }
+        values.push(value);
+        groups.last_mut().unwrap().0 += 1;
+    }
+    if groups.len() == 1 {
+        return Ok(radix::pack_digits(&values, 10));
+    }
+
+    // Check the group sizes from the right, blaming the separator after a
+    // bad group, or the one before it for the rightmost group.
+    let (first, rest) = locale.grouping.sizes();
+    let count = groups.len();
+    for (k, &(len, before)) in groups.iter().rev().enumerate() {
+        let valid = if k == 0 {
+            len == first
+        } else if k == count - 1 {
+            (1..=rest).contains(&len)
+        } else {
+            len == rest
+        };
+        if !valid {
+            let position = if k == 0 { before } else { groups[count - k].1 };
+            return Err(ParseError::new(
+                ParseErrorKind::MisplacedSeparator,
+                position.unwrap(),
+                Some(locale.separator),
+            ));
+        }
+    }
+
+    Ok(radix::pack_digits(&values, 10))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DigitShape, Grouping, NumberLocale};
+    use crate::{random_limbs, BigInt, BigUint, ParseError, ParseErrorKind};
+    use std::str::FromStr;
+
+    fn biguint(s: &str) -> BigUint {
+        BigUint::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_delimited_with() {
+        let n = biguint("1234567890");
+        assert_eq!(n.delimited().to_string(), "1,234,567,890");
+        assert_eq!(
+            n.delimited_with(NumberLocale::german()).to_string(),
+            "1.234.567.890"
+        );
+        assert_eq!(
+            n.delimited_with(NumberLocale::si()).to_string(),
+            "1\u{2009}234\u{2009}567\u{2009}890"
+        );
+        assert_eq!(
+            n.delimited_with(NumberLocale::indian()).to_string(),
+            "1,23,45,67,890"
+        );
+        assert_eq!(
+            n.delimited_with(NumberLocale::hindi()).to_string(),
+            "१,२३,४५,६७,८९०"
+        );
+        assert_eq!(
+            n.delimited_with(NumberLocale::arabic()).to_string(),
+            "١٬٢٣٤٬٥٦٧٬٨٩٠"
+        );
+        assert_eq!(
+            format!(
+                "{:>8}",
+                biguint("12345").delimited_with(NumberLocale::indian())
+            ),
+            "  12,345"
+        );
+        assert_eq!(
+            BigUint::zero()
+                .delimited_with(NumberLocale::hindi())
+                .to_string(),
+            "०"
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let locales = [
+            NumberLocale::english(),
+            NumberLocale::german(),
+            NumberLocale::si(),
+            NumberLocale::indian(),
+            NumberLocale::hindi(),
+            NumberLocale::arabic(),
+            NumberLocale::new(' ', Grouping::Indian, DigitShape::ArabicIndic),
+        ];
+        let mut seed = 7u64;
+        for len in 0..40 {
+            let n = BigUint::from_limbs(random_limbs(&mut seed, len % 5));
+            for locale in &locales {
+                let s = n.delimited_with(*locale).to_string();
+                assert_eq!(BigUint::from_delimited(&s, locale).unwrap(), n, "{}", s);
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_delimited() {
+        let english = NumberLocale::english();
+        let indian = NumberLocale::indian();
+        assert_eq!(
+            BigUint::from_delimited("1234567", &english).unwrap(),
+            BigUint::from(1234567u32)
+        );
+        assert_eq!(
+            BigUint::from_delimited("1,23,45,678", &NumberLocale::hindi()).unwrap(),
+            BigUint::from(12345678u32)
+        );
+        assert_eq!(
+            BigUint::from_delimited("१२,३४५", &NumberLocale::hindi()).unwrap(),
+            BigUint::from(12345u32)
+        );
+        assert_eq!(
+            BigUint::from_delimited("+1,000", &english).unwrap(),
+            BigUint::from_str("+1000").unwrap()
+        );
+        assert_eq!(
+            BigUint::from_delimited("+१,२३,४५६", &NumberLocale::hindi()).unwrap(),
+            BigUint::from(123456u32)
+        );
+        assert_eq!(
+            BigInt::from_delimited("-1.234", &NumberLocale::german()).unwrap(),
+            BigInt::from(-1234)
+        );
+        assert_eq!(
+            BigInt::from_delimited("+12,34,567", &indian).unwrap(),
+            BigInt::from(1234567)
+        );
+    }
+
+    #[test]
+    fn test_from_delimited_errors() {
+        let english = NumberLocale::english();
+        let error = |s: &str, locale: &NumberLocale| {
+            let e = BigInt::from_delimited(s, locale).unwrap_err();
+            (e.kind(), e.position())
+        };
+        assert_eq!(error("", &english), (ParseErrorKind::Empty, 0));
+        assert_eq!(error("-", &english), (ParseErrorKind::LoneSign, 0));
+        assert_eq!(error("12a4", &english), (ParseErrorKind::InvalidDigit, 2));
+        assert_eq!(error("१२", &english), (ParseErrorKind::InvalidDigit, 0));
+        let hindi = NumberLocale::hindi();
+        assert_eq!(error("१2,3४5", &hindi), (ParseErrorKind::InvalidDigit, 3));
+        assert_eq!(error("12,3४5", &hindi), (ParseErrorKind::InvalidDigit, 4));
+        assert_eq!(error("+", &english), (ParseErrorKind::LoneSign, 0));
+        let e = BigUint::from_delimited("-1,000", &english).unwrap_err();
+        assert_eq!((e.kind(), e.position()), (ParseErrorKind::InvalidDigit, 0));
+        let e = BigUint::from_delimited("+1,00", &english).unwrap_err();
+        assert_eq!(
+            (e.kind(), e.position()),
+            (ParseErrorKind::MisplacedSeparator, 2)
+        );
+        assert_eq!(error("1.234", &english), (ParseErrorKind::InvalidDigit, 1));
+        assert_eq!(
+            error("1,23", &english),
+            (ParseErrorKind::MisplacedSeparator, 1)
+        );
+        assert_eq!(
+            error("12,3,456", &english),
+            (ParseErrorKind::MisplacedSeparator, 4)
+        );
+        assert_eq!(
+            error("1234,567", &english),
+            (ParseErrorKind::MisplacedSeparator, 4)
+        );
+        assert_eq!(
+            error("-,123", &english),
+            (ParseErrorKind::MisplacedSeparator, 1)
+        );
+        assert_eq!(
+            error("123,", &english),
+            (ParseErrorKind::MisplacedSeparator, 3)
+        );
+        assert_eq!(
+            error("123,456", &NumberLocale::indian()),
+            (ParseErrorKind::MisplacedSeparator, 3)
+        );
+        assert_eq!(
+            error("1,234,567", &NumberLocale::indian()),
+            (ParseErrorKind::MisplacedSeparator, 5)
+        );
+        assert_eq!(
+            error("1\u{2009}23", &NumberLocale::si()),
+            (ParseErrorKind::MisplacedSeparator, 1)
+        );
+        assert_eq!(
+            ParseError::new(ParseErrorKind::MisplacedSeparator, 4, Some(',')).to_string(),
+            "misplaced separator at byte 4"
+        );
+    }
+}
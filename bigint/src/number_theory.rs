@@ -0,0 +1,318 @@
+use crate::{cmp_limbs, sub_assign_limbs, trim_zero_limbs, BigInt, LIMB_BITS};
+use std::cmp::Ordering;
+
+fn trailing_zero_bits(a: &[u32]) -> usize {
+    let zeros = a.iter().take_while(|&&limb| limb == 0).count();
+    zeros * LIMB_BITS as usize + a[zeros].trailing_zeros() as usize
+}
+
+fn shr_in_place(a: &mut Vec<u32>, shift: usize) {
+    let (limbs, bits) = (
+        shift / LIMB_BITS as usize,
+        (shift % LIMB_BITS as usize) as u32,
+    );
+    a.drain(..limbs);
+    if bits != 0 {
+        for i in 0..a.len() {
+            let high = a.get(i + 1).map_or(0, |&x| x << (LIMB_BITS - bits));
+            a[i] = (a[i] >> bits) | high;
+        }
+    }
+    trim_zero_limbs(a);
+}
+
+fn gcd_u64(mut a: u64, mut b: u64) -> u64 {
+    if a == 0 || b == 0 {
+        return a | b;
+    }
+    let shift = (a | b).trailing_zeros();
+    a >>= a.trailing_zeros();
+    loop {
+        b >>= b.trailing_zeros();
+        if a > b {
+            std::mem::swap(&mut a, &mut b);
+        }
+        b -= a;
+        if b == 0 {
+            return a << shift;
+        }
+    }
+}
+
+// Stein's binary GCD on magnitudes, switching to machine words once both
+// numbers fit in 64 bits.
+fn gcd_limbs(mut a: Vec<u32>, mut b: Vec<u32>) -> Vec<u32> {
+    if a.is_empty() {
+        return b;
+    }
+    if b.is_empty() {
+        return a;
+    }
+
+    let a_zeros = trailing_zero_bits(&a);
+    let b_zeros = trailing_zero_bits(&b);
+    let shift = a_zeros.min(b_zeros);
+    shr_in_place(&mut a, a_zeros);
+    shr_in_place(&mut b, b_zeros);
+
+    // Both are odd from here on.
+    loop {
+        if a.len() <= 2 && b.len() <= 2 {
+            let to_u64 = |x: &[u32]| x.iter().rev().fold(0, |acc, &l| (acc << 32) | l as u64);
+            let g = gcd_u64(to_u64(&a), to_u64(&b));
+            let g = BigInt::from(g) << shift;
+            return g.limbs;
+        }
+
+        match cmp_limbs(&a, &b) {
+            Ordering::Equal => break,
+            Ordering::Greater => std::mem::swap(&mut a, &mut b),
+            Ordering::Less => {}
+        }
+        sub_assign_limbs(&mut b, &a);
+        let zeros = trailing_zero_bits(&b);
+        shr_in_place(&mut b, zeros);
+    }
+
+    (BigInt::from_limbs(1, a) << shift).limbs
+}
+
+impl BigInt {
+    pub(crate) fn abs_value(&self) -> BigInt {
+        BigInt::from_limbs(1, self.limbs.clone())
+    }
+
+    // Remainder with the sign of the modulus, so it lands in [0, m) for a
+    // positive and in (m, 0] for a negative modulus.
+    pub(crate) fn mod_floor(&self, modulus: &BigInt) -> BigInt {
+        let remainder = self % modulus;
+        if !remainder.limbs.is_empty() && remainder.sign != modulus.sign {
+            remainder + modulus
+        } else {
+            remainder
+        }
+    }
+
+    // Always non-negative, and gcd(0, 0) is 0.
+    pub fn gcd(&self, other: &BigInt) -> BigInt {
+        BigInt::from_limbs(1, gcd_limbs(self.limbs.clone(), other.limbs.clone()))
+    }
+
+    // Returns (g, x, y) such that self * x + other * y == g == gcd(self, other).
+    pub fn extended_gcd(&self, other: &BigInt) -> (BigInt, BigInt, BigInt) {
+        let (mut old_r, mut r) = (self.abs_value(), other.abs_value());
+        let (mut old_s, mut s) = (BigInt::from(1u8), BigInt::new());
+        let (mut old_t, mut t) = (BigInt::new(), BigInt::from(1u8));
+
+        while !r.limbs.is_empty() {
+            let (quotient, remainder) = old_r.div_rem(&r).unwrap();
+            old_r = std::mem::replace(&mut r, remainder);
+            let next_s = old_s - &quotient * &s;
+            old_s = std::mem::replace(&mut s, next_s);
+            let next_t = old_t - &quotient * &t;
+            old_t = std::mem::replace(&mut t, next_t);
+        }
+
+        // The loop ran on the absolute values, so move the signs back into
+        // the coefficients.
+        if self.is_negative() {
+            old_s = -old_s;
+        }
+        if other.is_negative() {
+            old_t = -old_t;
+        }
+        (old_r, old_s, old_t)
+    }
+
+    // Always non-negative, and 0 if either number is 0.
+    pub fn lcm(&self, other: &BigInt) -> BigInt {
+        if self.limbs.is_empty() || other.limbs.is_empty() {
+            return BigInt::new();
+        }
+        let product = BigInt::from_limbs(1, crate::mul_limbs(&self.limbs, &other.limbs));
+        product / self.gcd(other)
+    }
+
+    // self^exp mod modulus, in [0, modulus) for a positive and in
+    // (modulus, 0] for a negative modulus.
+    //
+    // Panics if the modulus is zero or the exponent is negative.
+    pub fn mod_pow(&self, exp: &BigInt, modulus: &BigInt) -> BigInt {
+        assert!(!modulus.limbs.is_empty(), "modulus must not be zero");
+        assert!(!exp.is_negative(), "exponent must not be negative");
+
+        let base = self.mod_floor(modulus);
+        let mut result = BigInt::from(1u8).mod_floor(modulus);
+
+        for bit in (0..exp.bit_len()).rev() {
+            result = (&result * &result) % modulus;
+            if exp.test_bit(bit) {
+                result = (result * &base) % modulus;
+            }
+        }
+        result.mod_floor(modulus)
+    }
+
+    // The x in [0, |modulus|) with self * x == 1 (mod modulus), if there is one.
+    pub fn mod_inverse(&self, modulus: &BigInt) -> Option<BigInt> {
+        if modulus.limbs.is_empty() {
+            return None;
+        }
+
+        let (g, x, _) = self.extended_gcd(modulus);
+        if g != BigInt::from(1u8) {
+            return None;
+        }
+        Some(x.mod_floor(&modulus.abs_value()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{next_random, BigInt};
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(bigint("0").gcd(&bigint("0")), bigint("0"));
+        assert_eq!(bigint("0").gcd(&bigint("-7")), bigint("7"));
+        assert_eq!(bigint("48").gcd(&bigint("18")), bigint("6"));
+        assert_eq!(bigint("-48").gcd(&bigint("-180")), bigint("12"));
+        assert_eq!(bigint("17").gcd(&bigint("31")), bigint("1"));
+        assert_eq!(
+            (bigint("1") << 200).gcd(&(bigint("3") << 150)),
+            bigint("1") << 150
+        );
+        // Consecutive Fibonacci numbers are coprime, F(300) and F(150)
+        // share F(gcd(300, 150)) = F(150).
+        assert_eq!(
+            bigint("222232244629420445529739893461909967206666939096499764990979600")
+                .gcd(&bigint("9969216677189303386214405760200")),
+            bigint("9969216677189303386214405760200")
+        );
+        assert_eq!(
+            bigint("359579325206583560961765665172189099052367214309267232255589801").gcd(&bigint(
+                "222232244629420445529739893461909967206666939096499764990979600"
+            )),
+            bigint("1")
+        );
+        // (2^128 - 1) = (2^64 - 1)(2^64 + 1)
+        assert_eq!(
+            BigInt::from(u128::MAX).gcd(&BigInt::from(u64::MAX)),
+            BigInt::from(u64::MAX)
+        );
+    }
+
+    #[test]
+    fn test_gcd_matches_euclid() {
+        let mut seed = 9u64;
+        let mut next = || next_random(&mut seed);
+        for _ in 0..200 {
+            let common = BigInt::from(next() >> (next() % 64));
+            let a = &common * BigInt::from(next()) * BigInt::from(next() as u128 * 3);
+            let b = (&common * BigInt::from(next() >> 20)) << (next() % 70) as usize;
+
+            let (mut x, mut y) = (a.abs_value(), b.abs_value());
+            while y != BigInt::new() {
+                let r = &x % &y;
+                x = std::mem::replace(&mut y, r);
+            }
+            assert_eq!(a.gcd(&b), x);
+        }
+    }
+
+    #[test]
+    fn test_extended_gcd() {
+        let (g, x, y) = bigint("240").extended_gcd(&bigint("46"));
+        assert_eq!((g, x, y), (bigint("2"), bigint("-9"), bigint("47")));
+
+        for &(a, b) in [
+            ("0", "0"),
+            ("0", "5"),
+            ("-5", "0"),
+            ("-240", "46"),
+            ("240", "-46"),
+            ("-99", "-78"),
+            (
+                "340282366920938463463374607431768211455",
+                "18446744073709551617",
+            ),
+            ("1234567891011121314151617181920", "-98765432109876543210"),
+        ]
+        .iter()
+        {
+            let (a, b) = (bigint(a), bigint(b));
+            let (g, x, y) = a.extended_gcd(&b);
+            assert_eq!(g, a.gcd(&b));
+            assert_eq!(&a * x + &b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_lcm() {
+        assert_eq!(bigint("21").lcm(&bigint("6")), bigint("42"));
+        assert_eq!(bigint("-4").lcm(&bigint("6")), bigint("12"));
+        assert_eq!(bigint("0").lcm(&bigint("6")), bigint("0"));
+        assert_eq!(
+            BigInt::from(u64::MAX).lcm(&BigInt::from(u128::MAX)),
+            BigInt::from(u128::MAX)
+        );
+    }
+
+    #[test]
+    fn test_mod_pow() {
+        // Textbook RSA with p = 61, q = 53.
+        let (n, e, d) = (bigint("3233"), bigint("17"), bigint("2753"));
+        let c = bigint("65").mod_pow(&e, &n);
+        assert_eq!(c, bigint("2790"));
+        assert_eq!(c.mod_pow(&d, &n), bigint("65"));
+
+        assert_eq!(
+            bigint("4").mod_pow(&bigint("13"), &bigint("497")),
+            bigint("445")
+        );
+        assert_eq!(
+            bigint("-4").mod_pow(&bigint("3"), &bigint("7")),
+            bigint("6")
+        );
+        assert_eq!(
+            bigint("4").mod_pow(&bigint("3"), &bigint("-7")),
+            bigint("-6")
+        );
+        assert_eq!(bigint("5").mod_pow(&bigint("0"), &bigint("7")), bigint("1"));
+        assert_eq!(bigint("5").mod_pow(&bigint("0"), &bigint("1")), bigint("0"));
+
+        // Fermat's little theorem for the Mersenne prime 2^127 - 1.
+        let p = (bigint("1") << 127) - 1u8;
+        assert_eq!(bigint("123456789").mod_pow(&(&p - 1u8), &p), bigint("1"));
+        assert_eq!(bigint("2").mod_pow(&bigint("127"), &p), bigint("1"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mod_pow_zero_modulus() {
+        bigint("2").mod_pow(&bigint("3"), &bigint("0"));
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(bigint("3").mod_inverse(&bigint("11")), Some(bigint("4")));
+        assert_eq!(bigint("-3").mod_inverse(&bigint("11")), Some(bigint("7")));
+        assert_eq!(
+            bigint("17").mod_inverse(&bigint("3120")),
+            Some(bigint("2753"))
+        );
+        assert_eq!(bigint("6").mod_inverse(&bigint("9")), None);
+        assert_eq!(bigint("5").mod_inverse(&bigint("0")), None);
+        assert_eq!(bigint("5").mod_inverse(&bigint("1")), Some(bigint("0")));
+
+        let p = (bigint("1") << 521) - 1u8;
+        let a = bigint("98765432109876543210987654321");
+        let inverse = a.mod_inverse(&p).unwrap();
+        assert_eq!((a * inverse) % p, bigint("1"));
+    }
+}
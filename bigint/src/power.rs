@@ -0,0 +1,176 @@
+use crate::BigInt;
+
+impl BigInt {
+    pub fn pow(&self, mut exp: u32) -> BigInt {
+        let mut result = BigInt::from(1u8);
+        let mut base = self.clone();
+
+        while exp > 0 {
+            if exp & 1 == 1 {
+                result *= &base;
+            }
+            exp >>= 1;
+            if exp > 0 {
+                base = &base * &base;
+            }
+        }
+        result
+    }
+
+    // Floor of the square root. Panics for negative numbers.
+    pub fn isqrt(&self) -> BigInt {
+        assert!(!self.is_negative(), "square root of a negative number");
+        self.nth_root(2)
+    }
+
+    // The n-th root rounded towards zero, so negative numbers are fine for
+    // odd n. Panics if n is zero, or if n is even and the number is negative.
+    pub fn nth_root(&self, n: u32) -> BigInt {
+        assert!(n != 0, "zeroth root is undefined");
+        assert!(
+            !(self.is_negative() && n.is_multiple_of(2)),
+            "even root of a negative number"
+        );
+
+        if self.is_negative() {
+            return -BigInt::from_limbs(1, self.limbs.clone()).nth_root(n);
+        }
+        if n == 1 || self.bit_len() <= 1 {
+            return BigInt::from_limbs(1, self.limbs.clone());
+        }
+        // 1 < self < 2^n, so the root is 1. Newton would start at x = 2 and
+        // build 2^(n - 1) to find that out.
+        if self.bit_len() <= n as u64 {
+            return BigInt::from(1u8);
+        }
+
+        // Newton's iteration x' = ((n - 1) x + a / x^(n - 1)) / n decreases
+        // monotonically to the root when it starts above it, and
+        // 2^ceil(bits / n) always is.
+        let bits = self.bit_len().div_ceil(n as u64) as usize;
+        let mut x = BigInt::from(1u8) << bits;
+        loop {
+            let y = (&x * (n - 1) + self / x.pow(n - 1)) / n;
+            if y >= x {
+                return x;
+            }
+            x = y;
+        }
+    }
+
+    pub fn is_perfect_square(&self) -> bool {
+        if self.is_negative() {
+            return false;
+        }
+
+        // Squares can only end in a few patterns of bits, which rules out
+        // most numbers without computing the root.
+        let low = self.limbs.first().map_or(0, |&limb| limb & 63);
+        if (0x0202_0212_0203_0213u64 >> low) & 1 == 0 {
+            return false;
+        }
+
+        let root = self.isqrt();
+        &root * &root == *self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{random_limbs, BigInt};
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_pow() {
+        assert_eq!(bigint("2").pow(0), bigint("1"));
+        assert_eq!(bigint("0").pow(0), bigint("1"));
+        assert_eq!(bigint("0").pow(5), bigint("0"));
+        assert_eq!(bigint("-3").pow(3), bigint("-27"));
+        assert_eq!(bigint("-3").pow(4), bigint("81"));
+        assert_eq!(bigint("2").pow(127), BigInt::from(1u128 << 127));
+        assert_eq!(
+            bigint("10").pow(50),
+            bigint(&format!("1{}", "0".repeat(50)))
+        );
+
+        for base in -20i128..=20 {
+            for exp in 0..12 {
+                assert_eq!(BigInt::from(base).pow(exp), BigInt::from(base.pow(exp)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_known_roots() {
+        assert_eq!(bigint("0").isqrt(), bigint("0"));
+        assert_eq!(bigint("1").isqrt(), bigint("1"));
+        assert_eq!(bigint("3").isqrt(), bigint("1"));
+        assert_eq!(bigint("4").isqrt(), bigint("2"));
+        assert_eq!(bigint("99").isqrt(), bigint("9"));
+        assert_eq!(
+            bigint(&format!("1{}", "0".repeat(100))).isqrt(),
+            bigint(&format!("1{}", "0".repeat(50)))
+        );
+        assert_eq!(BigInt::from(u128::MAX).isqrt(), BigInt::from(u64::MAX));
+        assert_eq!(bigint("-27").nth_root(3), bigint("-3"));
+        assert_eq!(bigint("-28").nth_root(3), bigint("-3"));
+        assert_eq!(bigint("1000000").nth_root(6), bigint("10"));
+        assert_eq!(bigint("12345").nth_root(1), bigint("12345"));
+        assert_eq!(bigint("2").nth_root(100), bigint("1"));
+        assert_eq!(BigInt::from(3).nth_root(u32::MAX), bigint("1"));
+        assert_eq!(BigInt::from(-3).nth_root(u32::MAX), bigint("-1"));
+        assert_eq!(BigInt::from(u128::MAX).nth_root(128), bigint("1"));
+        assert_eq!(BigInt::from(1u128 << 127).nth_root(127), bigint("2"));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_isqrt_negative() {
+        bigint("-4").isqrt();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_even_root_negative() {
+        bigint("-4").nth_root(4);
+    }
+
+    #[test]
+    fn test_root_bounds() {
+        let mut seed = 21u64;
+        for len in [1usize, 2, 3, 7, 20, 50].iter() {
+            for _ in 0..5 {
+                let x = BigInt::from_limbs(1, random_limbs(&mut seed, *len));
+
+                for n in 2..8 {
+                    let r = x.nth_root(n);
+                    assert!(r.pow(n) <= x);
+                    assert!((r + 1u8).pow(n) > x);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_perfect_square() {
+        for n in 0u64..2000 {
+            let root = (n as f64).sqrt() as u64;
+            assert_eq!(
+                BigInt::from(n).is_perfect_square(),
+                root * root == n,
+                "{}",
+                n
+            );
+        }
+
+        let big = bigint("123456789012345678901234567890");
+        assert!((&big * &big).is_perfect_square());
+        assert!(!(&big * &big + 1u8).is_perfect_square());
+        assert!(!(&big * &big - 1u8).is_perfect_square());
+        assert!(!bigint("-4").is_perfect_square());
+    }
+}
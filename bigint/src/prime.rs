@@ -0,0 +1,373 @@
+use crate::{next_random, rem_small, BigInt};
+use std::sync::OnceLock;
+
+// Candidates are first divided by all primes below this bound, which rules
+// out most composites far more cheaply than a Miller-Rabin round.
+const SIEVE_LIMIT: usize = 2000;
+
+pub(crate) fn small_primes() -> &'static [u32] {
+    static PRIMES: OnceLock<Vec<u32>> = OnceLock::new();
+    PRIMES.get_or_init(|| {
+        let mut composite = vec![false; SIEVE_LIMIT];
+        let mut primes = Vec::new();
+        for i in 2..SIEVE_LIMIT {
+            if !composite[i] {
+                primes.push(i as u32);
+                for j in (i * i..SIEVE_LIMIT).step_by(i) {
+                    composite[j] = true;
+                }
+            }
+        }
+        primes
+    })
+}
+
+enum TrialDivision {
+    Prime,
+    Composite,
+    Unknown,
+}
+
+fn trial_division(n: &BigInt) -> TrialDivision {
+    if n.is_negative() || n.bit_len() <= 1 {
+        return TrialDivision::Composite;
+    }
+
+    for &p in small_primes() {
+        if n.limbs == [p] {
+            return TrialDivision::Prime;
+        }
+        if rem_small(&n.limbs, p) == 0 {
+            return TrialDivision::Composite;
+        }
+    }
+
+    if n.limbs.len() == 1 && (n.limbs[0] as u64) < (SIEVE_LIMIT * SIEVE_LIMIT) as u64 {
+        return TrialDivision::Prime;
+    }
+    TrialDivision::Unknown
+}
+
+// Strong probable prime test for an odd n > 2 with n - 1 == d * 2^s.
+fn miller_rabin(n: &BigInt, base: &BigInt) -> bool {
+    let n_minus_one = n - 1u8;
+    let s = n_minus_one.trailing_zeros().unwrap() as usize;
+    let d = &n_minus_one >> s;
+
+    let mut x = base.mod_pow(&d, n);
+    if x == BigInt::from(1u8) || x == n_minus_one {
+        return true;
+    }
+    for _ in 1..s {
+        x = (&x * &x) % n;
+        if x == n_minus_one {
+            return true;
+        }
+    }
+    false
+}
+
+// Jacobi symbol (a/n) for an odd positive n.
+fn jacobi(a: &BigInt, n: &BigInt) -> i32 {
+    let mut a = a.mod_floor(n);
+    let mut n = n.abs_value();
+    let mut result = 1;
+
+    while a != BigInt::new() {
+        let zeros = a.trailing_zeros().unwrap() as usize;
+        a >>= zeros;
+        if zeros % 2 == 1 && matches!(n.limbs[0] % 8, 3 | 5) {
+            result = -result;
+        }
+
+        std::mem::swap(&mut a, &mut n);
+        if a.limbs[0] % 4 == 3 && n.limbs[0] % 4 == 3 {
+            result = -result;
+        }
+        a = &a % &n;
+    }
+
+    if n == BigInt::from(1u8) {
+        result
+    } else {
+        0
+    }
+}
+
+// x / 2 mod n for an odd n.
+fn half_mod(x: BigInt, n: &BigInt) -> BigInt {
+    let x = x.mod_floor(n);
+    if x.test_bit(0) {
+        (x + n) >> 1
+    } else {
+        x >> 1
+    }
+}
+
+// Strong Lucas probable prime test with Selfridge's parameters, for an odd
+// n > 2 that is not a perfect square.
+fn strong_lucas(n: &BigInt) -> bool {
+    // The first D in 5, -7, 9, -11, ... with (D/n) == -1.
+    let mut d = 5i64;
+    loop {
+        match jacobi(&BigInt::from(d), n) {
+            -1 => break,
+            0 if BigInt::from(d.abs()) != *n => return false,
+            _ => d = if d > 0 { -d - 2 } else { -d + 2 },
+        }
+    }
+    let (p, q) = (BigInt::from(1u8), BigInt::from((1 - d) / 4));
+    let d = BigInt::from(d);
+
+    let n_plus_one = n + 1u8;
+    let s = n_plus_one.trailing_zeros().unwrap() as usize;
+    let k = &n_plus_one >> s;
+
+    // U_1 = 1, V_1 = P, then walk down the bits of k doubling the index and
+    // stepping by one where needed.
+    let mut u = BigInt::from(1u8);
+    let mut v = p.mod_floor(n);
+    let mut q_k = q.mod_floor(n);
+    for bit in (0..k.bit_len() - 1).rev() {
+        u = (&u * &v) % n;
+        v = (&v * &v - &q_k - &q_k).mod_floor(n);
+        q_k = (&q_k * &q_k) % n;
+
+        if k.test_bit(bit) {
+            let next_u = half_mod(&p * &u + &v, n);
+            v = half_mod(&d * &u + &p * &v, n);
+            u = next_u;
+            q_k = (&q_k * &q).mod_floor(n);
+        }
+    }
+
+    if u == BigInt::new() || v == BigInt::new() {
+        return true;
+    }
+    for _ in 1..s {
+        v = (&v * &v - &q_k - &q_k).mod_floor(n);
+        if v == BigInt::new() {
+            return true;
+        }
+        q_k = (&q_k * &q_k) % n;
+    }
+    false
+}
+
+impl BigInt {
+    // Miller-Rabin with the first `rounds` primes as bases, after trial
+    // division by the small primes. Composites pass a round with probability
+    // at most 1/4, and 12 rounds are exact for everything below 3.3 * 10^24.
+    // At least the base 2 round always runs, even for `rounds == 0`.
+    // Negative numbers, 0 and 1 are not prime.
+    pub fn is_probable_prime(&self, rounds: u32) -> bool {
+        match trial_division(self) {
+            TrialDivision::Prime => return true,
+            TrialDivision::Composite => return false,
+            TrialDivision::Unknown => {}
+        }
+
+        let primes = small_primes();
+        let mut seed = 0x9E37_79B9_7F4A_7C15u64;
+        (0..rounds.max(1) as usize).all(|i| {
+            let base = match primes.get(i) {
+                Some(&p) => BigInt::from(p),
+                None => {
+                    // Ran out of small primes, keep going with pseudo-random
+                    // bases in [2, n - 2].
+                    BigInt::from(next_random(&mut seed)) % (self - 3u8) + 2u8
+                }
+            };
+            miller_rabin(self, &base)
+        })
+    }
+
+    // Baillie-PSW: a Miller-Rabin round to base 2 followed by a strong Lucas
+    // test. No composite is known to pass it.
+    pub fn is_probable_prime_bpsw(&self) -> bool {
+        match trial_division(self) {
+            TrialDivision::Prime => true,
+            TrialDivision::Composite => false,
+            TrialDivision::Unknown => {
+                miller_rabin(self, &BigInt::from(2u8))
+                    && !self.is_perfect_square()
+                    && strong_lucas(self)
+            }
+        }
+    }
+
+    // The smallest prime bigger than `self`, according to Baillie-PSW.
+    pub fn next_prime(&self) -> BigInt {
+        if *self < BigInt::from(2u8) {
+            return BigInt::from(2u8);
+        }
+
+        let mut candidate = self + 1u8;
+        if !candidate.test_bit(0) {
+            if candidate == BigInt::from(2u8) {
+                return candidate;
+            }
+            candidate += 1u8;
+        }
+        while !candidate.is_probable_prime_bpsw() {
+            candidate += 2u8;
+        }
+        candidate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{jacobi, strong_lucas};
+    use crate::BigInt;
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    const CARMICHAEL: [&str; 13] = [
+        "561",
+        "1105",
+        "1729",
+        "2465",
+        "2821",
+        "6601",
+        "8911",
+        "41041",
+        "825265",
+        "321197185",
+        "5394826801",
+        "232250619601",
+        "9746347772161",
+    ];
+
+    // Strong pseudoprimes to base 2, the bigger ones to many more prime bases.
+    const STRONG_PSEUDOPRIMES: [&str; 7] = [
+        "2047",
+        "3277",
+        "4033",
+        "3215031751",
+        "3825123056546413051",
+        "318665857834031151167461",
+        "3317044064679887385961981",
+    ];
+
+    #[test]
+    fn test_small_numbers() {
+        let limit = 20_000;
+        let mut composite = vec![false; limit];
+        composite[0] = true;
+        composite[1] = true;
+        for i in 2..limit {
+            for j in (i * i..limit).step_by(i) {
+                composite[j] = true;
+            }
+        }
+
+        for (n, &is_composite) in composite.iter().enumerate() {
+            let big = BigInt::from(n);
+            assert_eq!(big.is_probable_prime(5), !is_composite, "{}", n);
+            assert_eq!(big.is_probable_prime_bpsw(), !is_composite, "{}", n);
+        }
+
+        assert!(!bigint("-7").is_probable_prime(10));
+        assert!(!bigint("-7").is_probable_prime_bpsw());
+    }
+
+    #[test]
+    fn test_carmichael_numbers() {
+        for n in CARMICHAEL.iter() {
+            assert!(!bigint(n).is_probable_prime(5), "{}", n);
+            assert!(!bigint(n).is_probable_prime_bpsw(), "{}", n);
+        }
+    }
+
+    #[test]
+    fn test_strong_pseudoprimes() {
+        for n in STRONG_PSEUDOPRIMES.iter() {
+            assert!(!bigint(n).is_probable_prime(20), "{}", n);
+            assert!(!bigint(n).is_probable_prime_bpsw(), "{}", n);
+        }
+
+        // Has no small factors and fools the first 11 prime bases.
+        assert!(bigint("3825123056546413051").is_probable_prime(11));
+        assert!(!bigint("3825123056546413051").is_probable_prime(12));
+    }
+
+    #[test]
+    fn test_zero_rounds() {
+        assert!(!bigint("998244359987710471").is_probable_prime(0));
+        assert!(bigint("1000000007").is_probable_prime(0));
+        assert!(!(bigint("1000000007") * bigint("998244353")).is_probable_prime(0));
+    }
+
+    #[test]
+    fn test_strong_lucas_pseudoprimes() {
+        // The Lucas test alone is fooled by these, the base 2 round is not.
+        for &n in [
+            5459u32, 5777, 10877, 16109, 18971, 22499, 24569, 25199, 40309, 58519,
+        ]
+        .iter()
+        {
+            let n = BigInt::from(n);
+            assert!(strong_lucas(&n), "{}", n);
+            assert!(!n.is_probable_prime_bpsw(), "{}", n);
+        }
+        assert!(!strong_lucas(&bigint("561")));
+        assert!(!strong_lucas(&bigint("2047")));
+        assert!(strong_lucas(&bigint("2003")));
+    }
+
+    #[test]
+    fn test_jacobi() {
+        assert_eq!(jacobi(&bigint("1001"), &bigint("9907")), -1);
+        assert_eq!(jacobi(&bigint("19"), &bigint("45")), 1);
+        assert_eq!(jacobi(&bigint("8"), &bigint("21")), -1);
+        assert_eq!(jacobi(&bigint("5"), &bigint("21")), 1);
+        assert_eq!(jacobi(&bigint("-7"), &bigint("21")), 0);
+        assert_eq!(jacobi(&bigint("-1"), &bigint("7")), -1);
+    }
+
+    #[test]
+    fn test_large_primes() {
+        let one = BigInt::from(1u8);
+        for &exp in [61usize, 89, 107, 127, 521].iter() {
+            let mersenne = (&one << exp) - 1u8;
+            assert!(mersenne.is_probable_prime(10), "2^{} - 1", exp);
+            assert!(mersenne.is_probable_prime_bpsw(), "2^{} - 1", exp);
+        }
+        for &exp in [67usize, 101, 128, 256].iter() {
+            let composite = (&one << exp) - 1u8;
+            assert!(!composite.is_probable_prime(10), "2^{} - 1", exp);
+            assert!(!composite.is_probable_prime_bpsw(), "2^{} - 1", exp);
+        }
+        // F5 = 2^32 + 1 = 641 * 6700417
+        assert!(!((&one << 32) + 1u8).is_probable_prime_bpsw());
+        // A product of two primes near 2^64
+        let semiprime = bigint("18446744073709551557") * bigint("18446744073709551533");
+        assert!(!semiprime.is_probable_prime(10));
+        assert!(!semiprime.is_probable_prime_bpsw());
+        // A square of a prime
+        assert!(!(bigint("1000000007") * bigint("1000000007")).is_probable_prime_bpsw());
+    }
+
+    #[test]
+    fn test_next_prime() {
+        assert_eq!(bigint("-10").next_prime(), bigint("2"));
+        assert_eq!(bigint("0").next_prime(), bigint("2"));
+        assert_eq!(bigint("1").next_prime(), bigint("2"));
+        assert_eq!(bigint("2").next_prime(), bigint("3"));
+        assert_eq!(bigint("3").next_prime(), bigint("5"));
+        assert_eq!(bigint("13").next_prime(), bigint("17"));
+        assert_eq!(bigint("7919").next_prime(), bigint("7927"));
+        assert_eq!(
+            bigint("100000000000000000000").next_prime(),
+            bigint("100000000000000000039")
+        );
+        assert_eq!(
+            (BigInt::from(1u8) << 64).next_prime(),
+            bigint("18446744073709551629")
+        );
+    }
+}
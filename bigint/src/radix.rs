@@ -0,0 +1,338 @@
+use crate::{div_rem_small, mul_add_small, trim_zero_limbs, BigInt, ParseError, ParseErrorKind};
+use std::fmt;
+
+impl BigInt {
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<Self, ParseError> {
+        if !(2..=36).contains(&radix) {
+            return Err(ParseError::new(ParseErrorKind::InvalidRadix, 0, None));
+        }
+
+        let (sign, digits) = split_sign(s)?;
+        let digits = match (radix, digits.get(..2)) {
+            (16, Some("0x")) | (16, Some("0X")) => &digits[2..],
+            (8, Some("0o")) | (8, Some("0O")) => &digits[2..],
+            (2, Some("0b")) | (2, Some("0B")) => &digits[2..],
+            _ => digits,
+        };
+
+        let limbs = parse_magnitude(digits, radix, s.len() - digits.len())?;
+        Ok(BigInt::from_limbs(sign, limbs))
+    }
+
+    // Panics if `radix` is not in the range from 2 to 36, same as `char::from_digit`.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = magnitude_to_radix(&self.limbs, radix);
+        if self.is_negative() {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+}
+
+pub(crate) fn split_sign(s: &str) -> Result<(i8, &str), ParseError> {
+    let (sign, digits) = match s.as_bytes().first() {
+        None => return Err(ParseError::new(ParseErrorKind::Empty, 0, None)),
+        Some(b'+') => (1, &s[1..]),
+        Some(b'-') => (-1, &s[1..]),
+        _ => (1, s),
+    };
+
+    if digits.is_empty() {
+        return Err(ParseError::new(
+            ParseErrorKind::LoneSign,
+            0,
+            s.chars().next(),
+        ));
+    }
+    Ok((sign, digits))
+}
+
+// Returns the radix given by a `0x`, `0o` or `0b` prefix and the digits after it.
+pub(crate) fn split_prefix(s: &str) -> (u32, &str) {
+    match s.get(..2) {
+        Some("0x") | Some("0X") => (16, &s[2..]),
+        Some("0o") | Some("0O") => (8, &s[2..]),
+        Some("0b") | Some("0B") => (2, &s[2..]),
+        _ => (10, s),
+    }
+}
+
+// The biggest power of `radix` that fits in a limb and its exponent.
+fn chunk_base(radix: u32) -> (u32, usize) {
+    let mut base = radix;
+    let mut digits = 1;
+    while let Some(next) = base.checked_mul(radix) {
+        base = next;
+        digits += 1;
+    }
+    (base, digits)
+}
+
+// `offset` is the position of `digits` in the whole input, so that errors
+// point at the right byte.
+pub(crate) fn parse_magnitude(
+    digits: &str,
+    radix: u32,
+    offset: usize,
+) -> Result<Vec<u32>, ParseError> {
+    if digits.is_empty() {
+        return Err(ParseError::new(ParseErrorKind::Empty, offset, None));
+    }
+
+    let values = digits
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(radix)
+                .ok_or_else(|| ParseError::new(ParseErrorKind::InvalidDigit, offset + i, Some(c)))
+        })
+        .collect::<Result<Vec<u32>, ParseError>>()?;
+
+    Ok(pack_digits(&values, radix))
+}
+
+// Limbs of the number with these digit values, most significant first. Every
+// value has to be below `radix`.
+pub(crate) fn pack_digits(values: &[u32], radix: u32) -> Vec<u32> {
+    if radix.is_power_of_two() {
+        // Every digit maps to a fixed number of bits, so they can be packed
+        // straight into the limbs.
+        let bits = radix.trailing_zeros();
+        let mut limbs = Vec::with_capacity(values.len() * bits as usize / 32 + 1);
+        let mut acc = 0u64;
+        let mut acc_bits = 0;
+        for &value in values.iter().rev() {
+            acc |= (value as u64) << acc_bits;
+            acc_bits += bits;
+            if acc_bits >= 32 {
+                limbs.push(acc as u32);
+                acc >>= 32;
+                acc_bits -= 32;
+            }
+        }
+        limbs.push(acc as u32);
+        trim_zero_limbs(&mut limbs);
+        return limbs;
+    }
+
+    let (_, chunk_digits) = chunk_base(radix);
+    let mut limbs = Vec::with_capacity(values.len() / chunk_digits + 1);
+    let first = match values.len() % chunk_digits {
+        0 => chunk_digits,
+        n => n,
+    };
+
+    let mut start = 0;
+    let mut end = first;
+    while start < values.len() {
+        let chunk = values[start..end]
+            .iter()
+            .fold(0, |acc, &value| acc * radix + value);
+        mul_add_small(&mut limbs, radix.pow((end - start) as u32), chunk);
+        start = end;
+        end += chunk_digits;
+    }
+
+    trim_zero_limbs(&mut limbs);
+    limbs
+}
+
+// Lowercase digits of the magnitude, most significant first.
+pub(crate) fn magnitude_to_radix(limbs: &[u32], radix: u32) -> String {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be in the range 2..=36"
+    );
+
+    if limbs.is_empty() {
+        return String::from("0");
+    }
+
+    let digit = |value: u32| std::char::from_digit(value, radix).unwrap();
+
+    if radix.is_power_of_two() {
+        let bits = radix.trailing_zeros() as usize;
+        let total_bits = limbs.len() * 32 - limbs[limbs.len() - 1].leading_zeros() as usize;
+        let mask = radix - 1;
+
+        return (0..total_bits.div_ceil(bits))
+            .rev()
+            .map(|i| {
+                let position = i * bits;
+                let (limb, offset) = (position / 32, position % 32);
+                let mut value = limbs[limb] >> offset;
+                if offset + bits > 32 && limb + 1 < limbs.len() {
+                    value |= limbs[limb + 1] << (32 - offset);
+                }
+                digit(value & mask)
+            })
+            .collect();
+    }
+
+    let (base, chunk_digits) = chunk_base(radix);
+    let mut chunks = Vec::with_capacity(limbs.len() * 32 / chunk_digits + 1);
+    let mut rest = limbs.to_vec();
+    while !rest.is_empty() {
+        let (quotient, chunk) = div_rem_small(&rest, base);
+        chunks.push(chunk);
+        rest = quotient;
+    }
+
+    let mut result = String::with_capacity(chunks.len() * chunk_digits);
+    for (i, &chunk) in chunks.iter().rev().enumerate() {
+        let mut chunk_str = Vec::with_capacity(chunk_digits);
+        let mut value = chunk;
+        while value != 0 {
+            chunk_str.push(digit(value % radix));
+            value /= radix;
+        }
+        // Every chunk but the most significant one is zero-padded.
+        if i != 0 {
+            chunk_str.resize(chunk_digits, '0');
+        }
+        result.extend(chunk_str.iter().rev());
+    }
+    result
+}
+
+impl fmt::LowerHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(
+            !self.is_negative(),
+            "0x",
+            &magnitude_to_radix(&self.limbs, 16),
+        )
+    }
+}
+
+impl fmt::UpperHex for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(
+            !self.is_negative(),
+            "0x",
+            &magnitude_to_radix(&self.limbs, 16).to_uppercase(),
+        )
+    }
+}
+
+impl fmt::Octal for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(
+            !self.is_negative(),
+            "0o",
+            &magnitude_to_radix(&self.limbs, 8),
+        )
+    }
+}
+
+impl fmt::Binary for BigInt {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad_integral(
+            !self.is_negative(),
+            "0b",
+            &magnitude_to_radix(&self.limbs, 2),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{next_random, random_limbs, BigInt};
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_from_str_radix() {
+        assert_eq!(BigInt::from_str_radix("ff", 16).unwrap(), bigint("255"));
+        assert_eq!(BigInt::from_str_radix("-FF", 16).unwrap(), bigint("-255"));
+        assert_eq!(BigInt::from_str_radix("0xff", 16).unwrap(), bigint("255"));
+        assert_eq!(BigInt::from_str_radix("+0o777", 8).unwrap(), bigint("511"));
+        assert_eq!(BigInt::from_str_radix("-0b101", 2).unwrap(), bigint("-5"));
+        assert_eq!(BigInt::from_str_radix("zz", 36).unwrap(), bigint("1295"));
+        assert_eq!(BigInt::from_str_radix("-0", 7).unwrap(), BigInt::new());
+        // A prefix is only stripped when it matches the radix.
+        assert_eq!(BigInt::from_str_radix("0b1", 16).unwrap(), bigint("177"));
+        assert_eq!(
+            BigInt::from_str_radix("ffffffffffffffffffffffffffffffff", 16).unwrap(),
+            BigInt::from(u128::MAX)
+        );
+        assert_eq!(
+            BigInt::from_str_radix("0000000000000000000000000001", 2).unwrap(),
+            bigint("1")
+        );
+
+        assert!(BigInt::from_str_radix("12", 2).is_err());
+        assert!(BigInt::from_str_radix("0x", 16).is_err());
+        assert!(BigInt::from_str_radix("0o8", 8).is_err());
+        assert!(BigInt::from_str_radix("-", 10).is_err());
+        assert!(BigInt::from_str_radix("1", 1).is_err());
+        assert!(BigInt::from_str_radix("1", 37).is_err());
+    }
+
+    #[test]
+    fn test_from_str_prefix() {
+        assert_eq!(bigint("0x1F"), bigint("31"));
+        assert_eq!(bigint("-0x1f"), bigint("-31"));
+        assert_eq!(bigint("+0o17"), bigint("15"));
+        assert_eq!(bigint("0b1111"), bigint("15"));
+        assert_eq!(bigint("0xDEADBEEF"), BigInt::from(0xDEAD_BEEFu32));
+
+        assert!(BigInt::from_str("0x").is_err());
+        assert!(BigInt::from_str("0x-1").is_err());
+        assert!(BigInt::from_str("0b2").is_err());
+        assert!(BigInt::from_str("1f").is_err());
+    }
+
+    #[test]
+    fn test_radix_round_trip() {
+        let mut seed = 5u64;
+        for radix in 2..=36 {
+            for len in [1, 2, 5, 17, 40].iter() {
+                let sign = if next_random(&mut seed) >> 63 == 1 {
+                    -1
+                } else {
+                    1
+                };
+                let n = BigInt::from_limbs(sign, random_limbs(&mut seed, *len));
+
+                let s = n.to_str_radix(radix);
+                assert_eq!(BigInt::from_str_radix(&s, radix).unwrap(), n);
+            }
+        }
+
+        for &n in [0u128, 1, 35, 36, 1295, u64::MAX as u128, u128::MAX].iter() {
+            for radix in 2..=36 {
+                assert_eq!(
+                    u128::from_str_radix(&BigInt::from(n).to_str_radix(radix), radix),
+                    Ok(n)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_format_flags() {
+        for &n in [0u128, 1, 255, 0xDEAD_BEEF, u64::MAX as u128 + 1, u128::MAX].iter() {
+            let big = BigInt::from(n);
+            assert_eq!(format!("{:x}", big), format!("{:x}", n));
+            assert_eq!(format!("{:X}", big), format!("{:X}", n));
+            assert_eq!(format!("{:o}", big), format!("{:o}", n));
+            assert_eq!(format!("{:b}", big), format!("{:b}", n));
+            assert_eq!(format!("{:#x}", big), format!("{:#x}", n));
+            assert_eq!(format!("{:#010X}", big), format!("{:#010X}", n));
+            assert_eq!(format!("{:>12o}", big), format!("{:>12o}", n));
+            assert_eq!(format!("{:*^#20b}", big), format!("{:*^#20b}", n));
+            assert_eq!(format!("{:+x}", big), format!("{:+x}", n));
+        }
+
+        let negative = bigint("-255");
+        assert_eq!(format!("{:x}", negative), "-ff");
+        assert_eq!(format!("{:#X}", negative), "-0xFF");
+        assert_eq!(format!("{:#010x}", negative), "-0x00000ff");
+        assert_eq!(format!("{:>8b}", bigint("-5")), "    -101");
+        assert_eq!(format!("{:#o}", bigint("-8")), "-0o10");
+    }
+}
@@ -0,0 +1,368 @@
+// Exact rational numbers. A `Ratio` is always in lowest terms with a positive
+// denominator, so every value has exactly one representation and the derived
+// equality is the numeric one.
+
+use crate::{radix, BigInt, DivisionByZero, ParseError, ParseErrorKind};
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numer: BigInt,
+    denom: BigInt,
+}
+
+impl Ratio {
+    pub fn new(numer: BigInt, denom: BigInt) -> Result<Ratio, DivisionByZero> {
+        if denom.limbs.is_empty() {
+            return Err(DivisionByZero);
+        }
+        Ok(Ratio::reduced(numer, denom))
+    }
+
+    pub fn from_integer(n: BigInt) -> Ratio {
+        Ratio {
+            numer: n,
+            denom: BigInt::from(1u8),
+        }
+    }
+
+    pub fn numer(&self) -> &BigInt {
+        &self.numer
+    }
+
+    pub fn denom(&self) -> &BigInt {
+        &self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom == BigInt::from(1u8)
+    }
+
+    pub fn is_positive(&self) -> bool {
+        self.numer.is_positive()
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.numer.is_negative()
+    }
+
+    // The denominator must not be zero.
+    fn reduced(numer: BigInt, denom: BigInt) -> Ratio {
+        let g = numer.gcd(&denom);
+        let (mut numer, mut denom) = (numer / &g, denom / &g);
+        if denom.is_negative() {
+            numer = -numer;
+            denom = -denom;
+        }
+        Ratio { numer, denom }
+    }
+}
+
+impl From<BigInt> for Ratio {
+    fn from(n: BigInt) -> Self {
+        Ratio::from_integer(n)
+    }
+}
+
+impl Default for Ratio {
+    fn default() -> Self {
+        Ratio::from_integer(BigInt::new())
+    }
+}
+
+// Accepts an integer, a fraction like "-3/4" or a decimal like "-1.25". Only
+// the numerator may have a sign and everything is in base 10.
+impl FromStr for Ratio {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (sign, rest) = radix::split_sign(s)?;
+        let offset = s.len() - rest.len();
+
+        if let Some(slash) = rest.find('/') {
+            let numer = radix::parse_magnitude(&rest[..slash], 10, offset)?;
+            let denom_offset = offset + slash + 1;
+            let denom = radix::parse_magnitude(&rest[slash + 1..], 10, denom_offset)?;
+            if denom.is_empty() {
+                return Err(ParseError::new(
+                    ParseErrorKind::ZeroDenominator,
+                    denom_offset,
+                    None,
+                ));
+            }
+            return Ok(Ratio::reduced(
+                BigInt::from_limbs(sign, numer),
+                BigInt::from_limbs(1, denom),
+            ));
+        }
+
+        let (whole, fraction) = match rest.find('.') {
+            Some(dot) => (&rest[..dot], &rest[dot + 1..]),
+            None => {
+                let whole = radix::parse_magnitude(rest, 10, offset)?;
+                return Ok(Ratio::from_integer(BigInt::from_limbs(sign, whole)));
+            }
+        };
+
+        // Both sides of the point need digits, so "1." and ".5" are errors.
+        let whole = BigInt::from_limbs(1, radix::parse_magnitude(whole, 10, offset)?);
+        let digits = radix::parse_magnitude(fraction, 10, s.len() - fraction.len())?;
+        let scale = BigInt::from(10u8).pow(fraction.len() as u32);
+        let numer = whole * &scale + BigInt::from_limbs(1, digits);
+        Ok(Ratio::reduced(BigInt::from_limbs(sign, numer.limbs), scale))
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Ratio) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Ratio) -> Ordering {
+        // The denominators are positive, so cross multiplying keeps the order.
+        if self.denom == other.denom {
+            return self.numer.cmp(&other.numer);
+        }
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl Add<&Ratio> for &Ratio {
+    type Output = Ratio;
+
+    fn add(self, other: &Ratio) -> Ratio {
+        if self.denom == other.denom {
+            return Ratio::reduced(&self.numer + &other.numer, self.denom.clone());
+        }
+        Ratio::reduced(
+            &self.numer * &other.denom + &other.numer * &self.denom,
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl Sub<&Ratio> for &Ratio {
+    type Output = Ratio;
+
+    fn sub(self, other: &Ratio) -> Ratio {
+        if self.denom == other.denom {
+            return Ratio::reduced(&self.numer - &other.numer, self.denom.clone());
+        }
+        Ratio::reduced(
+            &self.numer * &other.denom - &other.numer * &self.denom,
+            &self.denom * &other.denom,
+        )
+    }
+}
+
+impl Mul<&Ratio> for &Ratio {
+    type Output = Ratio;
+
+    fn mul(self, other: &Ratio) -> Ratio {
+        Ratio::reduced(&self.numer * &other.numer, &self.denom * &other.denom)
+    }
+}
+
+impl Div<&Ratio> for &Ratio {
+    type Output = Ratio;
+
+    fn div(self, other: &Ratio) -> Ratio {
+        if other.numer.limbs.is_empty() {
+            panic!("attempt to divide by zero");
+        }
+        Ratio::reduced(&self.numer * &other.denom, &self.denom * &other.numer)
+    }
+}
+
+macro_rules! forward_ratio_binop {
+    ($imp:ident, $method:ident) => {
+        impl $imp for Ratio {
+            type Output = Ratio;
+
+            fn $method(self, other: Ratio) -> Ratio {
+                (&self).$method(&other)
+            }
+        }
+
+        impl $imp<&Ratio> for Ratio {
+            type Output = Ratio;
+
+            fn $method(self, other: &Ratio) -> Ratio {
+                (&self).$method(other)
+            }
+        }
+
+        impl $imp<Ratio> for &Ratio {
+            type Output = Ratio;
+
+            fn $method(self, other: Ratio) -> Ratio {
+                self.$method(&other)
+            }
+        }
+    };
+}
+
+forward_ratio_binop!(Add, add);
+forward_ratio_binop!(Sub, sub);
+forward_ratio_binop!(Mul, mul);
+forward_ratio_binop!(Div, div);
+
+impl Neg for Ratio {
+    type Output = Ratio;
+
+    fn neg(self) -> Ratio {
+        Ratio {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Neg for &Ratio {
+    type Output = Ratio;
+
+    fn neg(self) -> Ratio {
+        Ratio {
+            numer: -&self.numer,
+            denom: self.denom.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Ratio;
+    use crate::{BigInt, ParseErrorKind};
+    use std::str::FromStr;
+
+    fn ratio(s: &str) -> Ratio {
+        Ratio::from_str(s).unwrap()
+    }
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_reduced() {
+        let r = Ratio::new(bigint("6"), bigint("-8")).unwrap();
+        assert_eq!(r.numer(), &bigint("-3"));
+        assert_eq!(r.denom(), &bigint("4"));
+
+        assert_eq!(
+            Ratio::new(bigint("0"), bigint("-5")).unwrap(),
+            Ratio::default()
+        );
+        assert_eq!(
+            Ratio::new(bigint("-12"), bigint("-1")).unwrap(),
+            ratio("12")
+        );
+        assert!(Ratio::new(bigint("1"), bigint("0")).is_err());
+
+        let big = bigint("123456789012345678901234567890");
+        let r = Ratio::new(&big * bigint("7"), &big * bigint("-21")).unwrap();
+        assert_eq!(r, ratio("-1/3"));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(ratio("3/4"), Ratio::new(bigint("3"), bigint("4")).unwrap());
+        assert_eq!(ratio("-1.25"), ratio("-5/4"));
+        assert_eq!(ratio("+0.50"), ratio("1/2"));
+        assert_eq!(ratio("-0.0"), ratio("0"));
+        assert_eq!(ratio("10/5"), ratio("2"));
+        assert!(ratio("17").is_integer());
+        assert_eq!(
+            ratio("0.000000000000000000000000000001"),
+            Ratio::new(bigint("1"), BigInt::from(10u8).pow(30)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let err = Ratio::from_str("").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::Empty);
+
+        let err = Ratio::from_str("3/0").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::ZeroDenominator);
+        assert_eq!(err.position(), 2);
+        assert_eq!(err.to_string(), "zero denominator at byte 2");
+
+        let err = Ratio::from_str("3/-4").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit);
+        assert_eq!((err.position(), err.character()), (2, Some('-')));
+
+        let err = Ratio::from_str("-1.2x").unwrap_err();
+        assert_eq!(err.kind(), ParseErrorKind::InvalidDigit);
+        assert_eq!(err.position(), 4);
+
+        for s in &["1.", ".5", "/2", "1/", "-"] {
+            assert!(Ratio::from_str(s).is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(ratio("-6/8").to_string(), "-3/4");
+        assert_eq!(ratio("4/2").to_string(), "2");
+        assert_eq!(ratio("0").to_string(), "0");
+        assert_eq!(ratio("1.05").to_string(), "21/20");
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(ratio("1/2") + ratio("1/3"), ratio("5/6"));
+        assert_eq!(ratio("1/6") + ratio("1/3"), ratio("1/2"));
+        assert_eq!(ratio("1/4") + ratio("3/4"), ratio("1"));
+        assert_eq!(ratio("1/2") - ratio("3/4"), ratio("-1/4"));
+        assert_eq!(&ratio("2/3") * &ratio("-9/4"), ratio("-3/2"));
+        assert_eq!(ratio("2/3") / ratio("-4/9"), ratio("-3/2"));
+        assert_eq!(-&ratio("2/3"), ratio("-2/3"));
+        assert_eq!(-ratio("0"), ratio("0"));
+
+        // The classic floating point trap is exact here.
+        assert_eq!(ratio("0.1") + ratio("0.2"), ratio("0.3"));
+
+        let mut sum = Ratio::default();
+        for k in 1u32..=20 {
+            sum = sum + Ratio::new(BigInt::from(1u8), BigInt::from(k)).unwrap();
+        }
+        assert_eq!(sum, ratio("55835135/15519504"));
+    }
+
+    #[test]
+    #[should_panic(expected = "attempt to divide by zero")]
+    fn test_div_by_zero() {
+        let _ = ratio("1/2") / ratio("0");
+    }
+
+    #[test]
+    fn test_order() {
+        let mut values: Vec<Ratio> = ["1/3", "-1/2", "0.3", "0", "-0.5", "2/7", "1"]
+            .iter()
+            .map(|s| ratio(s))
+            .collect();
+        values.sort();
+        let sorted: Vec<String> = values.iter().map(|r| r.to_string()).collect();
+        assert_eq!(sorted, ["-1/2", "-1/2", "0", "2/7", "3/10", "1/3", "1"]);
+
+        assert!(ratio("-1/3") > ratio("-1/2"));
+        assert!(ratio("5/7") < ratio("5/6"));
+        assert!(ratio("1/3").is_positive() && ratio("-1/3").is_negative());
+    }
+}
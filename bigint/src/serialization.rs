@@ -0,0 +1,176 @@
+// Serde support, behind the `serde` feature. A `BigInt` is written as a
+// decimal string so that no format loses precision; fields marked with
+// `#[serde(with = "bigint::serde_number")]` are written as numbers instead
+// whenever they fit in an `i64`. Both forms are accepted when reading.
+// Binary formats like bincode don't say which of the two they hold, so they
+// always get the string.
+
+use crate::BigInt;
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+impl Serialize for BigInt {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+struct BigintVisitor;
+
+impl<'de> Visitor<'de> for BigintVisitor {
+    type Value = BigInt;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "an integer or a string with a decimal integer")
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_i128<E: de::Error>(self, value: i128) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_u128<E: de::Error>(self, value: u128) -> Result<BigInt, E> {
+        Ok(BigInt::from(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<BigInt, E> {
+        BigInt::from_str(value).map_err(E::custom)
+    }
+}
+
+impl<'de> Deserialize<'de> for BigInt {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Binary formats can't tell a number from a string by looking at it,
+        // but they only ever see the string form.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BigintVisitor)
+        } else {
+            deserializer.deserialize_str(BigintVisitor)
+        }
+    }
+}
+
+pub mod number {
+    use super::BigintVisitor;
+    use crate::BigInt;
+    use serde::{Deserializer, Serializer};
+    use std::convert::TryFrom;
+
+    // Falls back to a string for values outside the `i64` range, and for
+    // formats that can't tell the two apart when reading.
+    pub fn serialize<S: Serializer>(value: &BigInt, serializer: S) -> Result<S::Ok, S::Error> {
+        match i64::try_from(value) {
+            Ok(small) if serializer.is_human_readable() => serializer.serialize_i64(small),
+            _ => serializer.collect_str(value),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BigInt, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BigintVisitor)
+        } else {
+            deserializer.deserialize_str(BigintVisitor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::BigInt;
+    use serde::{Deserialize, Serialize};
+    use std::str::FromStr;
+
+    fn bigint(s: &str) -> BigInt {
+        BigInt::from_str(s).unwrap()
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        id: BigInt,
+        #[serde(with = "crate::serde_number")]
+        amount: BigInt,
+    }
+
+    #[test]
+    fn test_string_by_default() {
+        let n = bigint("-123456789012345678901234567890");
+        let json = serde_json::to_string(&n).unwrap();
+        assert_eq!(json, "\"-123456789012345678901234567890\"");
+        assert_eq!(serde_json::from_str::<BigInt>(&json).unwrap(), n);
+        assert_eq!(serde_json::to_string(&bigint("7")).unwrap(), "\"7\"");
+    }
+
+    #[test]
+    fn test_number_mode() {
+        let record = Record {
+            id: bigint("42"),
+            amount: bigint("-9223372036854775808"),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"id":"42","amount":-9223372036854775808}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+
+        // Too big for an i64, so it stays a string.
+        let record = Record {
+            id: bigint("1"),
+            amount: bigint("9223372036854775808"),
+        };
+        let json = serde_json::to_string(&record).unwrap();
+        assert_eq!(json, r#"{"id":"1","amount":"9223372036854775808"}"#);
+        assert_eq!(serde_json::from_str::<Record>(&json).unwrap(), record);
+    }
+
+    #[test]
+    fn test_binary_format() {
+        for amount in ["-5", "9223372036854775808"].iter() {
+            let record = Record {
+                id: bigint("42"),
+                amount: bigint(amount),
+            };
+            let bytes = bincode::serialize(&record).unwrap();
+            assert_eq!(bincode::deserialize::<Record>(&bytes).unwrap(), record);
+        }
+        // The small amount is a string too, not an i64.
+        let bytes = bincode::serialize(&Record {
+            id: bigint("0"),
+            amount: bigint("-5"),
+        })
+        .unwrap();
+        assert_eq!(&bytes[bytes.len() - 10..], b"\x02\0\0\0\0\0\0\0-5");
+    }
+
+    #[test]
+    fn test_accepts_both_forms() {
+        let record: Record =
+            serde_json::from_str(r#"{"id":18446744073709551615,"amount":"-5"}"#).unwrap();
+        assert_eq!(record.id, bigint("18446744073709551615"));
+        assert_eq!(record.amount, bigint("-5"));
+
+        assert_eq!(serde_json::from_str::<BigInt>("0").unwrap(), bigint("0"));
+        assert_eq!(
+            serde_json::from_str::<BigInt>("\"0x1f\"").unwrap(),
+            bigint("31")
+        );
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        let err = serde_json::from_str::<BigInt>("\"12a\"").unwrap_err();
+        assert!(
+            err.to_string().contains("invalid digit 'a' at byte 2"),
+            "{}",
+            err
+        );
+        assert!(serde_json::from_str::<BigInt>("1.5").is_err());
+        assert!(serde_json::from_str::<BigInt>("null").is_err());
+    }
+}
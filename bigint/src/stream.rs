@@ -0,0 +1,369 @@
+// Parsing decimal numbers straight from a reader, for inputs too big to
+// want as one string. Only a fixed buffer of input is held at a time; the
+// digits are packed into limbs in blocks, and the blocks are merged in
+// pairs like a binary counter so that the big multiplications stay
+// balanced.
+
+use crate::{add_assign_limbs, mul_limbs, radix, BigInt, BigUint, ParseError, ParseErrorKind};
+use std::fmt;
+use std::io::{self, Read};
+
+const BUFFER_SIZE: usize = 1 << 16;
+const BLOCK_DIGITS: usize = 1 << 12;
+
+#[derive(Debug)]
+pub enum ReadError {
+    Io(io::Error),
+    Parse(ParseError),
+}
+
+impl fmt::Display for ReadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ReadError::Io(err) => write!(f, "read failed: {}", err),
+            ReadError::Parse(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for ReadError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ReadError::Io(err) => Some(err),
+            ReadError::Parse(err) => Some(err),
+        }
+    }
+}
+
+impl From<io::Error> for ReadError {
+    fn from(err: io::Error) -> Self {
+        ReadError::Io(err)
+    }
+}
+
+impl From<ParseError> for ReadError {
+    fn from(err: ParseError) -> Self {
+        ReadError::Parse(err)
+    }
+}
+
+impl BigInt {
+    // Reads a decimal number up to the end of `reader`. Digits may be
+    // separated by underscores anywhere, or by commas in groups of three as
+    // `delimited` writes them, and trailing whitespace such as a final
+    // newline is ignored. Error positions are byte offsets into the stream.
+    pub fn read_from<R: Read>(reader: R) -> Result<BigInt, ReadError> {
+        let (sign, limbs) = read_decimal(reader, true)?;
+        Ok(BigInt::from_limbs(sign, limbs))
+    }
+}
+
+impl BigUint {
+    // Like `BigInt::read_from`, without the minus sign.
+    pub fn read_from<R: Read>(reader: R) -> Result<BigUint, ReadError> {
+        let (_, limbs) = read_decimal(reader, false)?;
+        Ok(BigUint::from_limbs(limbs))
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum State {
+    Start,
+    Sign,
+    Digit,
+    // Just after the separator at this offset.
+    Separator(usize),
+    Trailing,
+}
+
+fn read_decimal<R: Read>(mut reader: R, signed: bool) -> Result<(i8, Vec<u32>), ReadError> {
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    let mut digits = Accumulator::new();
+    let mut sign = 1;
+    let mut state = State::Start;
+    let mut offset = 0;
+
+    // Digits since the last comma, which has to close a group of three.
+    let mut group = 0;
+    let mut last_comma = None;
+
+    loop {
+        let len = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(len) => len,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+            Err(err) => return Err(err.into()),
+        };
+
+        for (i, &byte) in buffer[..len].iter().enumerate() {
+            let position = offset + i;
+            let misplaced = || ParseError::new(ParseErrorKind::MisplacedSeparator, position, None);
+            state = match (byte, state) {
+                (b'0'..=b'9', State::Start | State::Sign | State::Digit | State::Separator(_)) => {
+                    digits.push((byte - b'0') as u32);
+                    group += 1;
+                    State::Digit
+                }
+                (b'-', State::Start) if signed => {
+                    sign = -1;
+                    State::Sign
+                }
+                (b'+', State::Start) => State::Sign,
+                (b'_', State::Digit) => State::Separator(position),
+                (b',', State::Digit) => {
+                    let valid = match last_comma {
+                        Some(_) => group == 3,
+                        None => group <= 3,
+                    };
+                    if !valid {
+                        return Err(misplaced().into());
+                    }
+                    group = 0;
+                    last_comma = Some(position);
+                    State::Separator(position)
+                }
+                (b'_' | b',', State::Start | State::Sign | State::Separator(_)) => {
+                    return Err(misplaced().into())
+                }
+                (b' ' | b'\t' | b'\r' | b'\n', State::Digit | State::Trailing) => State::Trailing,
+                _ => {
+                    let c = char_at(&buffer[i..len]);
+                    let err = ParseError::new(ParseErrorKind::InvalidDigit, position, Some(c));
+                    return Err(err.into());
+                }
+            };
+        }
+        offset += len;
+    }
+
+    let error = |kind, position| Err(ParseError::new(kind, position, None).into());
+    match state {
+        State::Start => error(ParseErrorKind::Empty, 0),
+        State::Sign => error(ParseErrorKind::LoneSign, 0),
+        State::Separator(position) => error(ParseErrorKind::MisplacedSeparator, position),
+        State::Digit | State::Trailing => match last_comma {
+            Some(position) if group != 3 => error(ParseErrorKind::MisplacedSeparator, position),
+            _ => Ok((sign, digits.finish())),
+        },
+    }
+}
+
+// The character at the start of `bytes`, or U+FFFD when it is not valid
+// UTF-8 or is cut off by the end of the buffer.
+fn char_at(bytes: &[u8]) -> char {
+    let bytes = &bytes[..bytes.len().min(4)];
+    let valid = match std::str::from_utf8(bytes) {
+        Ok(s) => s,
+        Err(err) => std::str::from_utf8(&bytes[..err.valid_up_to()]).unwrap(),
+    };
+    valid.chars().next().unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+// Digits, most significant first, packed as they arrive. Each entry of
+// `blocks` holds 2^level full blocks of digits, with the levels strictly
+// decreasing, so there are only logarithmically many of them.
+struct Accumulator {
+    digits: Vec<u32>,
+    blocks: Vec<(Vec<u32>, usize)>,
+    // 10^(BLOCK_DIGITS * 2^k) for each level k reached so far.
+    powers: Vec<Vec<u32>>,
+}
+
+impl Accumulator {
+    fn new() -> Self {
+        Accumulator {
+            digits: Vec::with_capacity(BLOCK_DIGITS),
+            blocks: Vec::new(),
+            powers: Vec::new(),
+        }
+    }
+
+    fn power(&mut self, level: usize) -> &[u32] {
+        while self.powers.len() <= level {
+            let next = match self.powers.last() {
+                Some(power) => mul_limbs(power, power),
+                None => BigInt::from(10u8).pow(BLOCK_DIGITS as u32).limbs,
+            };
+            self.powers.push(next);
+        }
+        &self.powers[level]
+    }
+
+    fn push(&mut self, digit: u32) {
+        self.digits.push(digit);
+        if self.digits.len() < BLOCK_DIGITS {
+            return;
+        }
+
+        let mut limbs = radix::pack_digits(&self.digits, 10);
+        self.digits.clear();
+        let mut level = 0;
+        while let Some(&(_, top)) = self.blocks.last() {
+            if top != level {
+                break;
+            }
+            let (high, _) = self.blocks.pop().unwrap();
+            let mut merged = mul_limbs(&high, self.power(level));
+            add_assign_limbs(&mut merged, &limbs);
+            limbs = merged;
+            level += 1;
+        }
+        self.blocks.push((limbs, level));
+    }
+
+    fn finish(mut self) -> Vec<u32> {
+        let mut limbs = radix::pack_digits(&self.digits, 10);
+        let mut scale = BigInt::from(10u8).pow(self.digits.len() as u32).limbs;
+        while let Some((high, level)) = self.blocks.pop() {
+            let mut merged = mul_limbs(&high, &scale);
+            add_assign_limbs(&mut merged, &limbs);
+            limbs = merged;
+            if !self.blocks.is_empty() {
+                scale = mul_limbs(&scale, self.power(level));
+            }
+        }
+        limbs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::random_limbs;
+    use std::str::FromStr;
+
+    // Hands out the input a few bytes at a time, so that digits, separators
+    // and multibyte characters get split across reads.
+    struct Trickle<'a> {
+        bytes: &'a [u8],
+        step: usize,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let len = self.step.min(buf.len()).min(self.bytes.len());
+            buf[..len].copy_from_slice(&self.bytes[..len]);
+            self.bytes = &self.bytes[len..];
+            Ok(len)
+        }
+    }
+
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+
+    fn read(s: &str) -> BigInt {
+        BigInt::read_from(s.as_bytes()).unwrap()
+    }
+
+    fn read_error(s: &str) -> (ParseErrorKind, usize, Option<char>) {
+        match BigInt::read_from(Trickle {
+            bytes: s.as_bytes(),
+            step: 3,
+        }) {
+            Err(ReadError::Parse(err)) => (err.kind(), err.position(), err.character()),
+            other => panic!("{:?} parsed as {:?}", s, other),
+        }
+    }
+
+    fn random_digits(len: usize, seed: &mut u64) -> String {
+        let mut digits: String = random_limbs(seed, len / 9 + 1)
+            .iter()
+            .map(|limb| format!("{:09}", limb % 1_000_000_000))
+            .collect();
+        digits.truncate(len);
+        digits
+    }
+
+    #[test]
+    fn test_matches_from_str() {
+        let mut seed = 11;
+        for &len in &[
+            1,
+            9,
+            BLOCK_DIGITS - 1,
+            BLOCK_DIGITS,
+            BLOCK_DIGITS + 1,
+            3 * BLOCK_DIGITS,
+            7 * BLOCK_DIGITS + 17,
+        ] {
+            let digits = random_digits(len, &mut seed);
+            let expected = BigInt::from_str(&digits).unwrap();
+            assert_eq!(read(&digits), expected, "{} digits", len);
+
+            let negative = format!("-{}\n", digits);
+            let trickle = Trickle {
+                bytes: negative.as_bytes(),
+                step: 1000,
+            };
+            assert_eq!(BigInt::read_from(trickle).unwrap(), -expected);
+        }
+    }
+
+    #[test]
+    fn test_leading_zeros_and_blocks() {
+        let mut digits = "0".repeat(2 * BLOCK_DIGITS);
+        digits.push_str("42");
+        assert_eq!(read(&digits), BigInt::from(42));
+        assert_eq!(read(&"0".repeat(BLOCK_DIGITS)), BigInt::zero());
+
+        let power = format!("1{}", "0".repeat(5 * BLOCK_DIGITS));
+        assert_eq!(read(&power), BigInt::from(10).pow(5 * BLOCK_DIGITS as u32));
+    }
+
+    #[test]
+    fn test_separators() {
+        assert_eq!(read("1_000_000"), BigInt::from(1000000));
+        assert_eq!(read("1_2_3"), BigInt::from(123));
+        assert_eq!(read("+1,234,567\r\n"), BigInt::from(1234567));
+        assert_eq!(read("-12,345"), BigInt::from(-12345));
+        assert_eq!(read("999,000 \n"), BigInt::from(999000));
+
+        let n: BigUint = "123456789012345678901234567890".parse().unwrap();
+        let delimited = n.delimited().to_string();
+        let trickle = Trickle {
+            bytes: delimited.as_bytes(),
+            step: 2,
+        };
+        assert_eq!(BigUint::read_from(trickle).unwrap(), n);
+    }
+
+    #[test]
+    fn test_errors() {
+        use ParseErrorKind::*;
+        assert_eq!(read_error(""), (Empty, 0, None));
+        assert_eq!(read_error("-"), (LoneSign, 0, None));
+        assert_eq!(read_error("12x4"), (InvalidDigit, 2, Some('x')));
+        assert_eq!(read_error("--1"), (InvalidDigit, 1, Some('-')));
+        assert_eq!(read_error(" 1"), (InvalidDigit, 0, Some(' ')));
+        assert_eq!(read_error("12 3"), (InvalidDigit, 3, Some('3')));
+        assert_eq!(read_error("1é"), (InvalidDigit, 1, Some('é')));
+        // Split between two reads.
+        assert_eq!(read_error("12é"), (InvalidDigit, 2, Some('\u{fffd}')));
+        assert_eq!(read_error("_1"), (MisplacedSeparator, 0, None));
+        assert_eq!(read_error("-,1"), (MisplacedSeparator, 1, None));
+        assert_eq!(read_error("1__2"), (MisplacedSeparator, 2, None));
+        assert_eq!(read_error("12_"), (MisplacedSeparator, 2, None));
+        assert_eq!(read_error("1234,567"), (MisplacedSeparator, 4, None));
+        assert_eq!(read_error("1,23,456"), (MisplacedSeparator, 4, None));
+        assert_eq!(read_error("1,234,56\n"), (MisplacedSeparator, 5, None));
+
+        let long = format!("{}?", "7".repeat(100000));
+        assert_eq!(read_error(&long), (InvalidDigit, 100000, Some('?')));
+
+        match BigUint::read_from("-5".as_bytes()) {
+            Err(ReadError::Parse(err)) => {
+                assert_eq!((err.kind(), err.position()), (InvalidDigit, 0))
+            }
+            other => panic!("{:?}", other),
+        }
+        match BigInt::read_from(Failing) {
+            Err(err @ ReadError::Io(_)) => assert_eq!(err.to_string(), "read failed: disk on fire"),
+            other => panic!("{:?}", other),
+        }
+    }
+}
@@ -0,0 +1,475 @@
+// Numbers written out in words, for cheques and invoices. The digits are
+// read off in groups of three (or six for the long scale), so there is no
+// upper limit: past the largest scale word the part above it is spelled
+// on its own, as in "one thousand vigintillion".
+
+use crate::{radix, BigInt};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Spelling {
+    // English with a billion of 10^9, "one billion two hundred million".
+    #[default]
+    ShortScale,
+    // English with a billion of 10^12, "one thousand two hundred million".
+    LongScale,
+    // Bulgarian, with a milliard of 10^9, "един милиард и двеста милиона".
+    Bulgarian,
+}
+
+pub struct Spelled<'a> {
+    negative: bool,
+    limbs: &'a [u32],
+    spelling: Spelling,
+}
+
+impl<'a> Spelled<'a> {
+    pub(crate) fn new(negative: bool, limbs: &'a [u32], spelling: Spelling) -> Self {
+        Spelled {
+            negative,
+            limbs,
+            spelling,
+        }
+    }
+}
+
+impl BigInt {
+    pub fn spelled(&self) -> Spelled<'_> {
+        self.spelled_with(Spelling::default())
+    }
+
+    pub fn spelled_with(&self, spelling: Spelling) -> Spelled<'_> {
+        Spelled::new(self.is_negative(), &self.limbs, spelling)
+    }
+}
+
+impl fmt::Display for Spelled<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let in_bulgarian = self.spelling == Spelling::Bulgarian;
+        let mut out = String::new();
+        if self.negative {
+            out.push_str(if in_bulgarian { "минус" } else { "minus" });
+        }
+
+        if self.limbs.is_empty() {
+            push_word(&mut out, if in_bulgarian { "нула" } else { "zero" });
+        } else {
+            let digits = radix::magnitude_to_radix(self.limbs, 10);
+            match self.spelling {
+                Spelling::ShortScale => english(&digits, false, &mut out),
+                Spelling::LongScale => english(&digits, true, &mut out),
+                Spelling::Bulgarian => bulgarian(&digits, Gender::Neuter, &mut out),
+            }
+        }
+        f.pad(&out)
+    }
+}
+
+fn push_word(out: &mut String, word: &str) {
+    if !out.is_empty() {
+        out.push(' ');
+    }
+    out.push_str(word);
+}
+
+// Splits decimal digits into groups of `width`, most significant first,
+// each with the power of 10^width it stands for.
+fn groups(digits: &str, width: usize) -> impl Iterator<Item = (usize, u32)> + '_ {
+    let count = digits.len().div_ceil(width);
+    let first = digits.len() - (count - 1) * width;
+    (0..count).map(move |k| {
+        let start = if k == 0 { 0 } else { first + (k - 1) * width };
+        let end = first + k * width;
+        (count - 1 - k, digits[start..end].parse().unwrap())
+    })
+}
+
+const ONES: [&str; 20] = [
+    "zero",
+    "one",
+    "two",
+    "three",
+    "four",
+    "five",
+    "six",
+    "seven",
+    "eight",
+    "nine",
+    "ten",
+    "eleven",
+    "twelve",
+    "thirteen",
+    "fourteen",
+    "fifteen",
+    "sixteen",
+    "seventeen",
+    "eighteen",
+    "nineteen",
+];
+
+const TENS: [&str; 10] = [
+    "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+];
+
+// Powers of 1000 in the short scale. The long scale uses the same words
+// from "million" on for powers of 1000000.
+const SCALES: [&str; 21] = [
+    "thousand",
+    "million",
+    "billion",
+    "trillion",
+    "quadrillion",
+    "quintillion",
+    "sextillion",
+    "septillion",
+    "octillion",
+    "nonillion",
+    "decillion",
+    "undecillion",
+    "duodecillion",
+    "tredecillion",
+    "quattuordecillion",
+    "quindecillion",
+    "sexdecillion",
+    "septendecillion",
+    "octodecillion",
+    "novemdecillion",
+    "vigintillion",
+];
+
+fn english(digits: &str, long: bool, out: &mut String) {
+    let (width, names): (usize, &[&str]) = if long {
+        (6, &SCALES[1..])
+    } else {
+        (3, &SCALES)
+    };
+
+    let top = width * names.len();
+    let digits = if digits.len() > top + width {
+        let (high, low) = digits.split_at(digits.len() - top);
+        english(high, long, out);
+        push_word(out, names[names.len() - 1]);
+        low
+    } else {
+        digits
+    };
+
+    for (index, value) in groups(digits, width) {
+        if value == 0 {
+            continue;
+        }
+        if value >= 1000 {
+            english_below_thousand(value / 1000, out);
+            push_word(out, "thousand");
+        }
+        if value % 1000 != 0 {
+            english_below_thousand(value % 1000, out);
+        }
+        if index > 0 {
+            push_word(out, names[index - 1]);
+        }
+    }
+}
+
+fn english_below_thousand(value: u32, out: &mut String) {
+    let (hundreds, rest) = (value / 100, value % 100);
+    if hundreds > 0 {
+        push_word(out, ONES[hundreds as usize]);
+        push_word(out, "hundred");
+    }
+    if rest >= 20 && rest % 10 != 0 {
+        let word = format!("{}-{}", TENS[rest as usize / 10], ONES[rest as usize % 10]);
+        push_word(out, &word);
+    } else if rest >= 20 {
+        push_word(out, TENS[rest as usize / 10]);
+    } else if rest > 0 {
+        push_word(out, ONES[rest as usize]);
+    }
+}
+
+// Bulgarian "one" and "two" agree with the noun they count: a plain number
+// is neuter, thousands are feminine and millions and up are masculine.
+#[derive(Clone, Copy)]
+enum Gender {
+    Neuter,
+    Feminine,
+    Masculine,
+}
+
+const BG_ONES: [&str; 20] = [
+    "нула",
+    "едно",
+    "две",
+    "три",
+    "четири",
+    "пет",
+    "шест",
+    "седем",
+    "осем",
+    "девет",
+    "десет",
+    "единадесет",
+    "дванадесет",
+    "тринадесет",
+    "четиринадесет",
+    "петнадесет",
+    "шестнадесет",
+    "седемнадесет",
+    "осемнадесет",
+    "деветнадесет",
+];
+
+const BG_TENS: [&str; 10] = [
+    "",
+    "",
+    "двадесет",
+    "тридесет",
+    "четиридесет",
+    "петдесет",
+    "шестдесет",
+    "седемдесет",
+    "осемдесет",
+    "деветдесет",
+];
+
+const BG_HUNDREDS: [&str; 10] = [
+    "",
+    "сто",
+    "двеста",
+    "триста",
+    "четиристотин",
+    "петстотин",
+    "шестстотин",
+    "седемстотин",
+    "осемстотин",
+    "деветстотин",
+];
+
+// Powers of 1000 from a million on, singular and counted.
+const BG_SCALES: [(&str, &str); 10] = [
+    ("милион", "милиона"),
+    ("милиард", "милиарда"),
+    ("трилион", "трилиона"),
+    ("квадрилион", "квадрилиона"),
+    ("квинтилион", "квинтилиона"),
+    ("секстилион", "секстилиона"),
+    ("септилион", "септилиона"),
+    ("октилион", "октилиона"),
+    ("нонилион", "нонилиона"),
+    ("децилион", "децилиона"),
+];
+
+// Bulgarian puts "и" (and) before the last number word of each group, "сто
+// двадесет и пет хиляди", and before a last group that is a single word,
+// "два милиона и сто хиляди".
+fn bulgarian(digits: &str, gender: Gender, out: &mut String) {
+    let top = 3 * (BG_SCALES.len() + 1);
+    let mut preceded = false;
+    let digits = if digits.len() > top + 3 {
+        let (high, low) = digits.split_at(digits.len() - top);
+        bulgarian(high, Gender::Masculine, out);
+        let (one, many) = BG_SCALES[BG_SCALES.len() - 1];
+        push_word(out, if high == "1" { one } else { many });
+        preceded = true;
+        low
+    } else {
+        digits
+    };
+
+    // Each group's words, and how many of them are numbers rather than
+    // the scale word after them.
+    let mut words: Vec<(Vec<&str>, usize)> = Vec::new();
+    for (index, value) in groups(digits, 3) {
+        if value == 0 {
+            continue;
+        }
+        let mut group = Vec::new();
+        match index {
+            0 => bulgarian_below_thousand(value, gender, &mut group),
+            1 if value == 1 => group.push("хиляда"),
+            1 => {
+                bulgarian_below_thousand(value, Gender::Feminine, &mut group);
+                group.push("хиляди");
+            }
+            _ => {
+                bulgarian_below_thousand(value, Gender::Masculine, &mut group);
+                let (one, many) = BG_SCALES[index - 2];
+                group.push(if value == 1 { one } else { many });
+            }
+        }
+        let numbers = if index == 0 || group.len() == 1 {
+            group.len()
+        } else {
+            group.len() - 1
+        };
+        if numbers > 1 {
+            group.insert(numbers - 1, "и");
+        }
+        words.push((group, numbers));
+    }
+
+    let groups = words.len();
+    if let Some((last, 1)) = words.last_mut() {
+        if groups > 1 || preceded {
+            last.insert(0, "и");
+        }
+    }
+    for word in words.iter().flat_map(|(group, _)| group) {
+        push_word(out, word);
+    }
+}
+
+fn bulgarian_below_thousand(value: u32, gender: Gender, out: &mut Vec<&str>) {
+    let unit = |n: u32| match (n, gender) {
+        (1, Gender::Feminine) => "една",
+        (1, Gender::Masculine) => "един",
+        (2, Gender::Masculine) => "два",
+        _ => BG_ONES[n as usize],
+    };
+
+    let (hundreds, rest) = (value / 100, value % 100);
+    if hundreds > 0 {
+        out.push(BG_HUNDREDS[hundreds as usize]);
+    }
+    if rest >= 20 {
+        out.push(BG_TENS[rest as usize / 10]);
+        if rest % 10 != 0 {
+            out.push(unit(rest % 10));
+        }
+    } else if rest > 0 {
+        out.push(unit(rest));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BigUint;
+
+    fn spell(s: &str, spelling: Spelling) -> String {
+        s.parse::<BigInt>()
+            .unwrap()
+            .spelled_with(spelling)
+            .to_string()
+    }
+
+    #[test]
+    fn test_short_scale() {
+        let short = |s| spell(s, Spelling::ShortScale);
+        assert_eq!(short("0"), "zero");
+        assert_eq!(short("7"), "seven");
+        assert_eq!(short("-13"), "minus thirteen");
+        assert_eq!(short("40"), "forty");
+        assert_eq!(short("99"), "ninety-nine");
+        assert_eq!(short("100"), "one hundred");
+        assert_eq!(short("1001"), "one thousand one");
+        assert_eq!(short("1000000"), "one million");
+        assert_eq!(
+            short("15688454648"),
+            "fifteen billion six hundred eighty-eight million four hundred fifty-four \
+             thousand six hundred forty-eight"
+        );
+        assert_eq!(
+            BigInt::from(u128::MAX).spelled().to_string(),
+            "three hundred forty undecillion two hundred eighty-two decillion three hundred \
+             sixty-six nonillion nine hundred twenty octillion nine hundred thirty-eight \
+             septillion four hundred sixty-three sextillion four hundred sixty-three \
+             quintillion three hundred seventy-four quadrillion six hundred seven trillion \
+             four hundred thirty-one billion seven hundred sixty-eight million two hundred \
+             eleven thousand four hundred fifty-five"
+        );
+    }
+
+    #[test]
+    fn test_beyond_the_scale_words() {
+        let vigintillion = format!("1{}", "0".repeat(63));
+        assert_eq!(
+            spell(&vigintillion, Spelling::ShortScale),
+            "one vigintillion"
+        );
+        assert_eq!(
+            spell(&format!("{}000", vigintillion), Spelling::ShortScale),
+            "one thousand vigintillion"
+        );
+        assert_eq!(
+            spell(&format!("2{}5", "0".repeat(125)), Spelling::ShortScale),
+            "two vigintillion vigintillion five"
+        );
+        assert_eq!(
+            spell(&format!("1{}", "0".repeat(126)), Spelling::LongScale),
+            "one million vigintillion"
+        );
+        assert_eq!(
+            spell(&format!("12{}", "0".repeat(33)), Spelling::Bulgarian),
+            "дванадесет децилиона"
+        );
+        assert_eq!(
+            spell(&format!("1{}1", "0".repeat(35)), Spelling::Bulgarian),
+            "хиляда децилиона и едно"
+        );
+        assert_eq!(
+            spell(&format!("1{}", "0".repeat(39)), Spelling::Bulgarian),
+            "един милион децилиона"
+        );
+    }
+
+    #[test]
+    fn test_long_scale() {
+        let long = |s| spell(s, Spelling::LongScale);
+        assert_eq!(long("0"), "zero");
+        assert_eq!(long("1000"), "one thousand");
+        assert_eq!(long("1000000"), "one million");
+        assert_eq!(long("1500000000"), "one thousand five hundred million");
+        assert_eq!(long("2000000000000"), "two billion");
+        assert_eq!(
+            long("-15688454648"),
+            "minus fifteen thousand six hundred eighty-eight million four hundred fifty-four \
+             thousand six hundred forty-eight"
+        );
+        assert_eq!(
+            long("1000000000001000"),
+            "one thousand billion one thousand"
+        );
+    }
+
+    #[test]
+    fn test_bulgarian() {
+        let bg = |s| spell(s, Spelling::Bulgarian);
+        assert_eq!(bg("0"), "нула");
+        assert_eq!(bg("1"), "едно");
+        assert_eq!(bg("2"), "две");
+        assert_eq!(bg("-15"), "минус петнадесет");
+        assert_eq!(bg("21"), "двадесет и едно");
+        assert_eq!(bg("101"), "сто и едно");
+        assert_eq!(bg("110"), "сто и десет");
+        assert_eq!(bg("121"), "сто двадесет и едно");
+        assert_eq!(bg("1000"), "хиляда");
+        assert_eq!(bg("1001"), "хиляда и едно");
+        assert_eq!(bg("2000"), "две хиляди");
+        assert_eq!(bg("21000"), "двадесет и една хиляди");
+        assert_eq!(bg("2150"), "две хиляди сто и петдесет");
+        assert_eq!(bg("1000000"), "един милион");
+        assert_eq!(bg("2000000"), "два милиона");
+        assert_eq!(bg("2100000"), "два милиона и сто хиляди");
+        assert_eq!(bg("1000021"), "един милион двадесет и едно");
+        assert_eq!(bg("125000"), "сто двадесет и пет хиляди");
+        assert_eq!(bg("1120000"), "един милион сто и двадесет хиляди");
+        assert_eq!(
+            bg("15688454648"),
+            "петнадесет милиарда шестстотин осемдесет и осем милиона четиристотин петдесет и \
+             четири хиляди шестстотин четиридесет и осем"
+        );
+    }
+
+    #[test]
+    fn test_biguint_and_padding() {
+        let n: BigUint = "1200".parse().unwrap();
+        assert_eq!(n.spelled().to_string(), "one thousand two hundred");
+        assert_eq!(
+            n.spelled_with(Spelling::Bulgarian).to_string(),
+            "хиляда и двеста"
+        );
+        assert_eq!(format!("{:>8}", BigUint::zero().spelled()), "    zero");
+        assert_eq!(format!("{:<6}|", BigInt::from(-1).spelled()), "minus one|");
+    }
+}
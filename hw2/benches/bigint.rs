@@ -0,0 +1,114 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use hw2::Bigint;
+use std::fmt::Debug;
+use std::str::FromStr;
+
+mod baseline;
+
+const SIZES: [usize; 3] = [1_000, 10_000, 100_000];
+
+fn digits(seed: u64, len: usize) -> String {
+    let mut state = seed;
+    (0..len)
+        .map(|i| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            let digit = (state >> 33) % 10;
+            (b'0' + if i == 0 { digit % 9 + 1 } else { digit } as u8) as char
+        })
+        .collect()
+}
+
+fn operands<T: FromStr>(len: usize) -> (T, T)
+where
+    T::Err: Debug,
+{
+    (
+        T::from_str(&digits(1, len)).unwrap(),
+        T::from_str(&digits(2, len)).unwrap(),
+    )
+}
+
+// Add, sub and cmp run on both the limbs and the original decimal digits
+// in `baseline`, so the reports show the two side by side.
+
+fn bench_add(c: &mut Criterion) {
+    let mut group = c.benchmark_group("add");
+    group.sample_size(10);
+    for &len in SIZES.iter() {
+        group.bench_with_input(BenchmarkId::new("limbs", len), &len, |b, &len| {
+            b.iter_batched(
+                || operands::<Bigint>(len),
+                |(x, y)| black_box(x + y),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("digits", len), &len, |b, &len| {
+            b.iter_batched(
+                || operands::<baseline::Bigint>(len),
+                |(x, y)| black_box(x + y),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_sub(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sub");
+    group.sample_size(10);
+    for &len in SIZES.iter() {
+        group.bench_with_input(BenchmarkId::new("limbs", len), &len, |b, &len| {
+            b.iter_batched(
+                || operands::<Bigint>(len),
+                |(x, y)| black_box(x - y),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("digits", len), &len, |b, &len| {
+            b.iter_batched(
+                || operands::<baseline::Bigint>(len),
+                |(x, y)| black_box(x - y),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn bench_cmp(c: &mut Criterion) {
+    let mut group = c.benchmark_group("cmp");
+    for &len in SIZES.iter() {
+        let (x, _) = operands::<Bigint>(len);
+        let (y, _) = operands::<Bigint>(len);
+        group.bench_with_input(BenchmarkId::new("limbs", len), &len, |b, _| {
+            b.iter(|| black_box(x.cmp(&y)))
+        });
+
+        let (x, _) = operands::<baseline::Bigint>(len);
+        let (y, _) = operands::<baseline::Bigint>(len);
+        group.bench_with_input(BenchmarkId::new("digits", len), &len, |b, _| {
+            b.iter(|| black_box(x.cmp(&y)))
+        });
+    }
+    group.finish();
+}
+
+fn bench_mul(c: &mut Criterion) {
+    let mut group = c.benchmark_group("mul");
+    group.sample_size(10);
+    for &len in [100, 1_000, 10_000, 50_000].iter() {
+        group.bench_with_input(BenchmarkId::from_parameter(len), &len, |b, &len| {
+            b.iter_batched(
+                || operands::<Bigint>(len),
+                |(x, y)| black_box(x * y),
+                criterion::BatchSize::LargeInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_add, bench_sub, bench_cmp, bench_mul);
+criterion_main!(benches);
@@ -0,0 +1,512 @@
+// An arbitrary precision calculator. Every command line argument is one
+// statement; without arguments the statements are read from stdin, one per
+// line. A statement is either an expression like `(2^521 - 1) % 7 * -3` or an
+// assignment like `x = 0xff * 2`.
+
+use hw2::Bigint;
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt;
+use std::io::{self, BufRead};
+use std::process;
+use std::str::FromStr;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut calculator = Calculator::new();
+    let mut failed = false;
+
+    let mut run = |line: &str| match calculator.execute(line) {
+        Ok(Some(value)) => println!("{}", value),
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("error: {}", err);
+            failed = true;
+        }
+    };
+
+    if args.is_empty() {
+        for line in io::stdin().lock().lines() {
+            match line {
+                Ok(line) => run(&line),
+                Err(err) => {
+                    eprintln!("error: {}", err);
+                    process::exit(1);
+                }
+            }
+        }
+    } else {
+        for arg in args.iter() {
+            run(arg);
+        }
+    }
+
+    if failed {
+        process::exit(1);
+    }
+}
+
+// Powers and products are refused when the result would be bigger than
+// this, rather than running out of memory over a typo like `10^4000000000`
+// or a chain of `x * x * x`.
+const MAX_RESULT_BITS: u64 = 1 << 22;
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct CalcError {
+    // 1-based, in characters.
+    column: usize,
+    message: String,
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "column {}: {}", self.column, self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Number(Bigint),
+    Ident(String),
+    Op(char),
+}
+
+// A token with the column it starts at.
+type Spanned = (Token, usize);
+
+fn tokenize(line: &str) -> Result<Vec<Spanned>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = line.char_indices().peekable();
+    let column = |byte: usize| line[..byte].chars().count() + 1;
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c.is_ascii_alphanumeric() || c == '_' {
+            // Take the whole word, so that `0x1fg` is reported as a bad
+            // literal rather than a number followed by a name.
+            let mut end = start;
+            while let Some(&(i, c)) = chars.peek() {
+                if !(c.is_ascii_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = i + c.len_utf8();
+                chars.next();
+            }
+
+            let word = &line[start..end];
+            if c.is_ascii_digit() {
+                let number = Bigint::from_str(word).map_err(|err| CalcError {
+                    column: column(start + err.position()),
+                    message: format!("invalid literal `{}`: {}", word, err),
+                })?;
+                tokens.push((Token::Number(number), column(start)));
+            } else {
+                tokens.push((Token::Ident(word.to_string()), column(start)));
+            }
+        } else if "+-*/%^(),=".contains(c) {
+            tokens.push((Token::Op(c), column(start)));
+            chars.next();
+        } else {
+            return Err(CalcError {
+                column: column(start),
+                message: format!("unexpected character {:?}", c),
+            });
+        }
+    }
+    Ok(tokens)
+}
+
+pub struct Calculator {
+    variables: HashMap<String, Bigint>,
+}
+
+impl Calculator {
+    pub fn new() -> Self {
+        Calculator {
+            variables: HashMap::new(),
+        }
+    }
+
+    // Returns the value of an expression, or `None` for assignments and
+    // blank lines. `#` starts a comment.
+    pub fn execute(&mut self, line: &str) -> Result<Option<Bigint>, CalcError> {
+        let code = line.split('#').next().unwrap_or("");
+        let tokens = tokenize(code)?;
+        if tokens.is_empty() {
+            return Ok(None);
+        }
+
+        let end = code.chars().count() + 1;
+        let assignment = match tokens.as_slice() {
+            [(Token::Ident(name), _), (Token::Op('='), _), ..] => Some(name.clone()),
+            _ => None,
+        };
+        let skip = if assignment.is_some() { 2 } else { 0 };
+
+        let mut parser = Parser {
+            tokens: &tokens[skip..],
+            position: 0,
+            end,
+            variables: &self.variables,
+        };
+        let value = parser.expression()?;
+        if let Some((token, column)) = parser.peek() {
+            return Err(parser.error(column, format!("unexpected {}", describe(token))));
+        }
+
+        match assignment {
+            Some(name) => {
+                self.variables.insert(name, value);
+                Ok(None)
+            }
+            None => Ok(Some(value)),
+        }
+    }
+}
+
+impl Default for Calculator {
+    fn default() -> Self {
+        Calculator::new()
+    }
+}
+
+fn describe(token: &Token) -> String {
+    match token {
+        Token::Number(n) => format!("number {}", n),
+        Token::Ident(name) => format!("name `{}`", name),
+        Token::Op(c) => format!("`{}`", c),
+    }
+}
+
+// Recursive descent, evaluating as it goes. From loosest to tightest:
+// `+ -`, `* / %`, unary minus, and right associative `^`, so -2^2 is -4.
+struct Parser<'a> {
+    tokens: &'a [Spanned],
+    position: usize,
+    // Column just past the end of the line, for errors at the end.
+    end: usize,
+    variables: &'a HashMap<String, Bigint>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<(&Token, usize)> {
+        self.tokens
+            .get(self.position)
+            .map(|(token, column)| (token, *column))
+    }
+
+    fn error(&self, column: usize, message: String) -> CalcError {
+        CalcError { column, message }
+    }
+
+    fn eat(&mut self, op: char) -> Option<usize> {
+        match self.peek() {
+            Some((Token::Op(c), column)) if *c == op => {
+                self.position += 1;
+                Some(column)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: char) -> Result<(), CalcError> {
+        if self.eat(op).is_some() {
+            return Ok(());
+        }
+        Err(match self.peek() {
+            Some((token, column)) => self.error(
+                column,
+                format!("expected `{}`, found {}", op, describe(token)),
+            ),
+            None => self.error(self.end, format!("expected `{}` at the end", op)),
+        })
+    }
+
+    fn expression(&mut self) -> Result<Bigint, CalcError> {
+        let mut value = self.term()?;
+        loop {
+            if self.eat('+').is_some() {
+                value += self.term()?;
+            } else if self.eat('-').is_some() {
+                value -= self.term()?;
+            } else {
+                return Ok(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Result<Bigint, CalcError> {
+        let mut value = self.unary()?;
+        loop {
+            let (op, column) = match self.peek() {
+                Some((Token::Op(c), column)) if "*/%".contains(*c) => (*c, column),
+                _ => return Ok(value),
+            };
+            self.position += 1;
+
+            let rhs = self.unary()?;
+            if op != '*' && rhs.is_zero() {
+                return Err(self.error(column, String::from("division by zero")));
+            }
+            if op == '*' {
+                // A product of a bits by b bits has at least a + b - 1 bits.
+                let bits = (value.bit_len() + rhs.bit_len()).saturating_sub(1);
+                self.check_bits(bits, column)?;
+            }
+            value = match op {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+    }
+
+    fn unary(&mut self) -> Result<Bigint, CalcError> {
+        if self.eat('-').is_some() {
+            return Ok(-self.unary()?);
+        }
+        if self.eat('+').is_some() {
+            return self.unary();
+        }
+        self.power()
+    }
+
+    fn power(&mut self) -> Result<Bigint, CalcError> {
+        let base = self.primary()?;
+        match self.eat('^') {
+            Some(column) => {
+                let exponent = self.unary()?;
+                self.pow(&base, &exponent, column)
+            }
+            None => Ok(base),
+        }
+    }
+
+    fn pow(&self, base: &Bigint, exponent: &Bigint, column: usize) -> Result<Bigint, CalcError> {
+        let exponent = u32::try_from(exponent).map_err(|_| {
+            self.error(
+                column,
+                format!("exponent {} is not in the range 0..2^32", exponent),
+            )
+        })?;
+
+        // base^exponent has at least (bits - 1) * exponent bits, which is
+        // exact enough and leaves 0^n and 1^n alone.
+        let bits = base
+            .bit_len()
+            .saturating_sub(1)
+            .saturating_mul(exponent as u64);
+        self.check_bits(bits, column)?;
+        Ok(base.pow(exponent))
+    }
+
+    fn check_bits(&self, bits: u64, column: usize) -> Result<(), CalcError> {
+        if bits > MAX_RESULT_BITS {
+            return Err(self.error(
+                column,
+                format!(
+                    "result would have over {} bits, the limit is {}",
+                    bits, MAX_RESULT_BITS
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn primary(&mut self) -> Result<Bigint, CalcError> {
+        let (token, column) = match self.peek() {
+            Some((token, column)) => (token.clone(), column),
+            None => return Err(self.error(self.end, String::from("expected a value at the end"))),
+        };
+        self.position += 1;
+
+        match token {
+            Token::Number(n) => Ok(n),
+            Token::Op('(') => {
+                let value = self.expression()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Token::Ident(name) => {
+                if self.eat('(').is_some() {
+                    return self.call(&name, column);
+                }
+                self.variables
+                    .get(&name)
+                    .cloned()
+                    .ok_or_else(|| self.error(column, format!("unknown variable `{}`", name)))
+            }
+            token => Err(self.error(
+                column,
+                format!("expected a value, found {}", describe(&token)),
+            )),
+        }
+    }
+
+    // Called after the opening parenthesis.
+    fn call(&mut self, name: &str, column: usize) -> Result<Bigint, CalcError> {
+        let mut args = Vec::new();
+        if self.eat(')').is_none() {
+            loop {
+                args.push(self.expression()?);
+                if self.eat(',').is_none() {
+                    self.expect(')')?;
+                    break;
+                }
+            }
+        }
+
+        let arity = match name {
+            "gcd" | "pow" => 2,
+            _ => return Err(self.error(column, format!("unknown function `{}`", name))),
+        };
+        if args.len() != arity {
+            return Err(self.error(
+                column,
+                format!("`{}` takes {} arguments, not {}", name, arity, args.len()),
+            ));
+        }
+
+        match name {
+            "gcd" => Ok(args[0].gcd(&args[1])),
+            _ => self.pow(&args[0], &args[1], column),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CalcError, Calculator};
+    use hw2::Bigint;
+    use std::str::FromStr;
+
+    fn eval(line: &str) -> String {
+        Calculator::new()
+            .execute(line)
+            .unwrap()
+            .unwrap()
+            .to_string()
+    }
+
+    fn error(line: &str) -> String {
+        Calculator::new().execute(line).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn test_arithmetic() {
+        assert_eq!(eval("1 + 2 * 3"), "7");
+        assert_eq!(eval("(1 + 2) * 3"), "9");
+        assert_eq!(eval("(2^521 - 1) % 7 * -3"), "-9");
+        assert_eq!(eval("2^3^2"), "512");
+        assert_eq!(eval("-2^2"), "-4");
+        assert_eq!(eval("(-2)^3"), "-8");
+        assert_eq!(eval("7 / -2"), "-3");
+        assert_eq!(eval("-7 % 2"), "-1");
+        assert_eq!(eval("--5 + +1"), "6");
+        assert_eq!(eval("10 - 4 - 3"), "3");
+        assert_eq!(eval("0xff + 0b101 + 0o17"), "275");
+        assert_eq!(
+            eval("2^200"),
+            "1606938044258990275541962092341162602522202993782792835301376"
+        );
+    }
+
+    #[test]
+    fn test_functions_and_variables() {
+        assert_eq!(eval("gcd(2^40 * 3, 6^20)"), "3145728");
+        assert_eq!(eval("pow(3, 4) - pow(-2, 3)"), "89");
+        assert_eq!(eval("1^4000000000 + 0^4000000000"), "1");
+        assert_eq!(eval("pow(-1, 4000000001)"), "-1");
+        assert_eq!(eval("(2^4194304 - 1) % 1000"), "15");
+
+        let mut calculator = Calculator::new();
+        assert_eq!(calculator.execute("x = 2^127 - 1"), Ok(None));
+        assert_eq!(calculator.execute("  # nothing here"), Ok(None));
+        assert_eq!(calculator.execute("y = x * 2 # comment"), Ok(None));
+        assert_eq!(
+            calculator.execute("y - x"),
+            Ok(Some(
+                Bigint::from_str("170141183460469231731687303715884105727").unwrap()
+            ))
+        );
+        assert_eq!(calculator.execute("x = x + 1"), Ok(None));
+        assert_eq!(
+            calculator.execute("x").unwrap().unwrap(),
+            Bigint::one() << 127
+        );
+    }
+
+    #[test]
+    fn test_literal_errors() {
+        assert_eq!(
+            error("1 + 0x1fg"),
+            "column 9: invalid literal `0x1fg`: invalid digit 'g' at byte 4"
+        );
+        assert_eq!(
+            error("12a4 * 2"),
+            "column 3: invalid literal `12a4`: invalid digit 'a' at byte 2"
+        );
+        assert_eq!(
+            error("3 * 0b"),
+            "column 7: invalid literal `0b`: no digits to parse at byte 2"
+        );
+        assert_eq!(error("2 $ 3"), "column 3: unexpected character '$'");
+    }
+
+    #[test]
+    fn test_errors() {
+        let cases = [
+            ("1 / (2 - 2)", 3, "division by zero"),
+            ("5 % 0", 3, "division by zero"),
+            ("2 ^ -1", 3, "exponent -1 is not in the range 0..2^32"),
+            (
+                "10^4000000000",
+                3,
+                "result would have over 12000000000 bits, the limit is 4194304",
+            ),
+            (
+                "pow(3, 4000000000)",
+                1,
+                "result would have over 4000000000 bits, the limit is 4194304",
+            ),
+            (
+                "2^4000000 * 2^4000000",
+                11,
+                "result would have over 8000001 bits, the limit is 4194304",
+            ),
+            (
+                "pow(pow(2, 4000000), 1) * 2^200000",
+                25,
+                "result would have over 4200001 bits, the limit is 4194304",
+            ),
+            ("(1 + 2", 7, "expected `)` at the end"),
+            ("1 +", 4, "expected a value at the end"),
+            ("1 2", 3, "unexpected number 2"),
+            ("z + 1", 1, "unknown variable `z`"),
+            ("lcm(2, 3)", 1, "unknown function `lcm`"),
+            ("gcd(2)", 1, "`gcd` takes 2 arguments, not 1"),
+            ("gcd(1, 2 3)", 10, "expected `)`, found number 3"),
+            ("* 3", 1, "expected a value, found `*`"),
+        ];
+        for &(line, column, message) in cases.iter() {
+            assert_eq!(
+                Calculator::new().execute(line),
+                Err(CalcError {
+                    column,
+                    message: message.to_string()
+                }),
+                "{}",
+                line
+            );
+        }
+
+        let mut calculator = Calculator::new();
+        assert_eq!(calculator.execute("x = 2^4000000"), Ok(None));
+        assert_eq!(
+            calculator.execute("x * x * x").unwrap_err().to_string(),
+            "column 3: result would have over 8000001 bits, the limit is 4194304"
+        );
+        assert_eq!(calculator.execute("0 * x * x"), Ok(Some(Bigint::zero())));
+    }
+}